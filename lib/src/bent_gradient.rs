@@ -1,15 +1,14 @@
 use crate::{
-    blur, designer,
+    designer,
     lab_ui::LabUi,
     utils::{
         oklab_to_srgb, oklab_to_srgb_clipped, oklab_to_vec3, render_par, resettable_slider,
-        vec3_to_oklab, NEUTRAL_LAB,
+        smooth_par, vec3_to_oklab, NEUTRAL_LAB,
     },
+    working_space::WorkingSpace,
 };
-use palette::{convert::FromColorUnclamped, Oklab, Srgb};
-use rayon::iter::{
-    IndexedParallelIterator, IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator,
-};
+use eframe::emath::Rect;
+use palette::{Oklab, Srgb};
 
 #[derive(PartialEq, Clone)]
 pub struct Gradient {
@@ -20,6 +19,7 @@ pub struct Gradient {
     y_slope: Oklab,
     y2_slope: Oklab,
     y3_slope: Oklab,
+    space: WorkingSpace,
     extend: bool,
     smooth: f32,
 }
@@ -54,6 +54,7 @@ impl Gradient {
             y_slope: Self::Y_SLOPE_DEFAULT,
             y2_slope: Self::Y2_SLOPE_DEFAULT,
             y3_slope: Self::Y3_SLOPE_DEFAULT,
+            space: WorkingSpace::new(),
             extend: true,
             smooth: Self::SMOOTH_DEFAULT,
         }
@@ -71,22 +72,30 @@ impl designer::Designer for Gradient {
             y_slope,
             y2_slope,
             y3_slope,
+            space,
             extend,
             smooth,
         } = &mut c;
         ui.vertical(|ui| {
-            ui.add(LabUi::new(center, "center").default_value(Self::CENTER_DEFAULT));
+            space.show_ui(ui);
+            ui.add(
+                LabUi::new(center, "center")
+                    .default_value(Self::CENTER_DEFAULT)
+                    .space(space),
+            );
             let slope2_range = 2. * Oklab::<f32>::min_a()..=2. * Oklab::<f32>::max_a();
             let slope3_range = 2. * Oklab::<f32>::min_a()..=2. * Oklab::<f32>::max_a();
             ui.add(
                 LabUi::new(x_slope, "x")
                     .default_value(Self::X_SLOPE_DEFAULT)
-                    .l_range(-Oklab::<f32>::max_l()..=Oklab::max_l()),
+                    .l_range(-Oklab::<f32>::max_l()..=Oklab::max_l())
+                    .picker(false),
             );
             ui.add(
                 LabUi::new(x2_slope, "x2")
                     .default_value(Self::X2_SLOPE_DEFAULT)
                     .l_range(-Oklab::<f32>::max_l()..=Oklab::max_l())
+                    .picker(false)
                     .a_range(slope2_range.clone())
                     .b_range(slope2_range.clone()),
             );
@@ -94,18 +103,21 @@ impl designer::Designer for Gradient {
                 LabUi::new(x3_slope, "x3")
                     .default_value(Self::X3_SLOPE_DEFAULT)
                     .l_range(-Oklab::<f32>::max_l()..=Oklab::max_l())
+                    .picker(false)
                     .a_range(slope3_range.clone())
                     .b_range(slope3_range.clone()),
             );
             ui.add(
                 LabUi::new(y_slope, "y")
                     .default_value(Self::Y_SLOPE_DEFAULT)
-                    .l_range(-Oklab::<f32>::max_l()..=Oklab::max_l()),
+                    .l_range(-Oklab::<f32>::max_l()..=Oklab::max_l())
+                    .picker(false),
             );
             ui.add(
                 LabUi::new(y2_slope, "y2")
                     .default_value(Self::Y2_SLOPE_DEFAULT)
                     .l_range(-Oklab::<f32>::max_l()..=Oklab::max_l())
+                    .picker(false)
                     .a_range(slope2_range.clone())
                     .b_range(slope2_range),
             );
@@ -113,6 +125,7 @@ impl designer::Designer for Gradient {
                 LabUi::new(y3_slope, "y3")
                     .default_value(Self::Y3_SLOPE_DEFAULT)
                     .l_range(-Oklab::<f32>::max_l()..=Oklab::max_l())
+                    .picker(false)
                     .a_range(slope3_range.clone())
                     .b_range(slope3_range),
             );
@@ -129,19 +142,23 @@ impl designer::Designer for Gradient {
         }
     }
 
-    fn render(&self, size: (usize, usize), buf: &mut [Srgb]) {
-        render_par(size, buf, |x, y| {
-            let xcenter = x - 0.5;
-            let ycenter = y - 0.5;
-            let lab = vec3_to_oklab(
-                oklab_to_vec3(self.center)
-                    + xcenter * oklab_to_vec3(self.x_slope)
-                    + xcenter.powi(2) * oklab_to_vec3(self.x2_slope)
-                    + xcenter.powi(3) * oklab_to_vec3(self.x3_slope)
-                    + ycenter * oklab_to_vec3(self.y_slope)
-                    + ycenter.powi(2) * oklab_to_vec3(self.y2_slope)
-                    + ycenter.powi(3) * oklab_to_vec3(self.y3_slope),
-            );
+    fn sample(&self, x: f32, y: f32) -> Oklab {
+        let xcenter = x - 0.5;
+        let ycenter = y - 0.5;
+        self.space.to_oklab(vec3_to_oklab(
+            oklab_to_vec3(self.center)
+                + xcenter * oklab_to_vec3(self.x_slope)
+                + xcenter.powi(2) * oklab_to_vec3(self.x2_slope)
+                + xcenter.powi(3) * oklab_to_vec3(self.x3_slope)
+                + ycenter * oklab_to_vec3(self.y_slope)
+                + ycenter.powi(2) * oklab_to_vec3(self.y2_slope)
+                + ycenter.powi(3) * oklab_to_vec3(self.y3_slope),
+        ))
+    }
+
+    fn render(&self, size: (usize, usize), region: Rect, buf: &mut [Srgb]) {
+        render_par(size, region, buf, |x, y| {
+            let lab = self.sample(x, y);
             if self.extend {
                 oklab_to_srgb_clipped(lab)
             } else {
@@ -149,17 +166,7 @@ impl designer::Designer for Gradient {
             }
         });
         if self.smooth > 0. && self.extend {
-            // TODO have rayon split the work into bigger chunks to reduce sync?
-            let mut labbuf: Vec<_> = buf
-                .par_iter()
-                .map(|c| palette::Oklab::from_color_unclamped(c.into_linear()))
-                .collect();
-            blur::gaussian_blur(labbuf.as_mut(), size.0, size.1, self.smooth);
-            labbuf
-                .par_iter()
-                .copied()
-                .zip(buf.par_iter_mut())
-                .for_each(|(a, b)| *b = oklab_to_srgb_clipped(a));
+            smooth_par(size, region, buf, self.smooth);
         }
     }
 }
@@ -17,26 +17,21 @@ where
         + Send,
 {
     debug_assert!(buf.len() == w * h);
-    // TODO use non-in-place transpose to allow non-square
-    assert!(w == h, "we only handle square input currently");
     for fw in box_widths_for_gauss_3(sigma) {
         average_filter_x(buf, w, h, fw);
     }
-    transpose_square(buf, w);
+    let mut transposed = transpose(buf, w, h);
     for fw in box_widths_for_gauss_3(sigma) {
-        average_filter_x(buf, w, h, fw);
+        average_filter_x(&mut transposed, h, w, fw);
     }
-    transpose_square(buf, w);
+    buf.copy_from_slice(&transpose(&transposed, h, w));
 }
 
-fn transpose_square<T>(buf: &mut [T], size: usize) {
-    debug_assert!(buf.len() == size.pow(2));
-    for y in 0..(size - 1) {
-        for x in (y + 1)..size {
-            // TODO unchecked?
-            buf.swap(y * size + x, x * size + y);
-        }
-    }
+fn transpose<T: Copy>(buf: &[T], w: usize, h: usize) -> Vec<T> {
+    debug_assert!(buf.len() == w * h);
+    (0..w)
+        .flat_map(|x| (0..h).map(move |y| buf[y * w + x]))
+        .collect()
 }
 
 fn box_widths_for_gauss_3(sigma: f32) -> [u32; 3] {
@@ -91,17 +86,14 @@ where
             for _ in 0..rd {
                 acc += inp[0];
             }
-            for i in &inp[0..=rd] {
-                acc += *i;
+            // clamp indices to handle filters wider than the image
+            for i in 0..=rd {
+                acc += inp[i.min(w - 1)];
             }
             for x in 0..w {
                 out[x] = acc / filter_width as f32;
-                acc += if x >= w - rd - 1 {
-                    *inp.last().unwrap()
-                } else {
-                    inp[x + rd + 1]
-                };
-                acc -= if x < rd { inp[0] } else { inp[x - rd] };
+                acc += inp[(x + rd + 1).min(w - 1)];
+                acc -= inp[x.saturating_sub(rd)];
             }
         });
 }
@@ -0,0 +1,326 @@
+use std::ops::RangeInclusive;
+
+use eframe::{
+    egui::{self, Sense},
+    emath::{pos2, Pos2, Rect},
+    epaint::Color32,
+};
+use palette::Srgb;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use crate::{
+    cvd::{Deficiency, Simulation},
+    designer::Designer,
+    utils::{resettable_slider, UNIT_RECT},
+};
+
+/// How color vision deficiency simulation is shown in the canvas.
+#[derive(EnumIter, Debug, PartialEq, Default, Copy, Clone)]
+pub enum CvdPreview {
+    #[default]
+    Off,
+    Simulated,
+    /// normal and simulated side by side
+    Split,
+}
+
+/// Zoomable and pannable view of a design, rendered at the physical pixel size of the screen.
+pub struct Canvas {
+    /// the point of the design shown in the middle of the canvas
+    center: Pos2,
+    /// 1 means the whole design just fits in the canvas
+    zoom: f32,
+    /// evaluate the designer outside of its 0..1 domain
+    extrapolate: bool,
+    cvd_preview: CvdPreview,
+    simulation: Simulation,
+    rendered: Option<Rendered>,
+}
+
+struct Rendered {
+    size: (usize, usize),
+    region: Rect,
+    buf: Vec<Srgb>,
+    texture: egui::TextureHandle,
+    /// the cvd simulated render, along with the simulation used to create it
+    simulated: Option<(Simulation, egui::TextureHandle)>,
+}
+
+fn to_color_image(buf: &[Srgb], size: (usize, usize)) -> egui::ColorImage {
+    let u8buf: Vec<u8> = buf
+        .iter()
+        .flat_map(|p| {
+            let q = p.into_format();
+            [q.red, q.green, q.blue, u8::MAX]
+        })
+        .collect();
+    egui::ColorImage::from_rgba_unmultiplied([size.0, size.1], u8buf.as_ref())
+}
+
+impl Canvas {
+    const CENTER_DEFAULT: Pos2 = pos2(0.5, 0.5);
+    const ZOOM_DEFAULT: f32 = 1.;
+    const ZOOM_RANGE: RangeInclusive<f32> = 0.05..=1000.;
+    const SCROLL_ZOOM_SPEED: f32 = 0.002;
+
+    pub fn new() -> Self {
+        Self {
+            center: Self::CENTER_DEFAULT,
+            zoom: Self::ZOOM_DEFAULT,
+            extrapolate: false,
+            cvd_preview: CvdPreview::default(),
+            simulation: Simulation::new(),
+            rendered: None,
+        }
+    }
+
+    /// Make the next frame re-render the design.
+    pub fn invalidate(&mut self) {
+        self.rendered = None;
+    }
+
+    fn reset_view(&mut self) {
+        self.center = Self::CENTER_DEFAULT;
+        self.zoom = Self::ZOOM_DEFAULT;
+    }
+
+    /// The parts of the canvas that each show the design, and whether they are cvd simulated.
+    fn views(&self, rect: Rect) -> Vec<(Rect, bool)> {
+        match self.cvd_preview {
+            CvdPreview::Off => vec![(rect, false)],
+            CvdPreview::Simulated => vec![(rect, true)],
+            CvdPreview::Split => {
+                let (left, right) = rect.split_left_right_at_fraction(0.5);
+                vec![(left, false), (right, true)]
+            }
+        }
+    }
+
+    /// The parts of the canvas that each show the whole design.
+    pub fn view_rects(&self, response: &egui::Response) -> Vec<Rect> {
+        self.views(response.rect)
+            .into_iter()
+            .map(|(view, _)| view)
+            .collect()
+    }
+
+    /// screen points per unit of the design
+    fn scale(&self, view: Rect) -> f32 {
+        view.width().min(view.height()) * self.zoom
+    }
+
+    fn to_design(&self, view: Rect, pos: Pos2) -> Pos2 {
+        self.center + (pos - view.center()) / self.scale(view)
+    }
+
+    /// Where a point of the design is shown on screen in one of the [`Canvas::view_rects`].
+    pub fn to_screen(&self, view: Rect, pos: Pos2) -> Pos2 {
+        view.center() + (pos - self.center) * self.scale(view)
+    }
+
+    /// The point of the design under the pointer, if the canvas is hovered.
+    pub fn hover_pos(&self, response: &egui::Response) -> Option<Pos2> {
+        let pos = response.hover_pos()?;
+        let (view, _) = self
+            .views(response.rect)
+            .into_iter()
+            .find(|(view, _)| view.contains(pos))?;
+        Some(self.to_design(view, pos))
+    }
+
+    /// The final color shown at a point of the design, if it is currently rendered.
+    pub fn rendered_color(&self, pos: Pos2) -> Option<Srgb> {
+        let rendered = self.rendered.as_ref()?;
+        if !rendered.region.contains(pos) {
+            return None;
+        }
+        let t = (pos - rendered.region.min) / rendered.region.size();
+        let x = ((t.x * rendered.size.0 as f32) as usize).min(rendered.size.0 - 1);
+        let y = ((t.y * rendered.size.1 as f32) as usize).min(rendered.size.1 - 1);
+        Some(rendered.buf[y * rendered.size.0 + x])
+    }
+
+    /// The current render as `(pixels, size, region of the design)`.
+    pub fn rendered(&self) -> Option<(&[Srgb], (usize, usize), Rect)> {
+        let rendered = self.rendered.as_ref()?;
+        Some((&rendered.buf, rendered.size, rendered.region))
+    }
+
+    pub fn show_options(&mut self, ui: &mut egui::Ui) {
+        ui.checkbox(&mut self.extrapolate, "extrapolate")
+            .on_hover_text("evaluate the design outside of its normal domain");
+        ui.label(format!("{:.0}%", self.zoom * 100.));
+        if ui
+            .add_enabled(
+                self.center != Self::CENTER_DEFAULT || self.zoom != Self::ZOOM_DEFAULT,
+                egui::Button::new("⟲"),
+            )
+            .on_hover_text("reset view")
+            .clicked()
+        {
+            self.reset_view();
+        }
+        ui.menu_button("cvd", |ui| {
+            for preview in CvdPreview::iter() {
+                ui.radio_value(&mut self.cvd_preview, preview, format!("{:?}", preview));
+            }
+            ui.separator();
+            for deficiency in Deficiency::iter() {
+                ui.radio_value(
+                    &mut self.simulation.deficiency,
+                    deficiency,
+                    format!("{:?}", deficiency),
+                );
+            }
+            resettable_slider(
+                ui,
+                &mut self.simulation.severity,
+                "severity",
+                0. ..=1.,
+                Simulation::SEVERITY_DEFAULT,
+            );
+        })
+        .response
+        .on_hover_text("color vision deficiency simulation");
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, designer: &dyn Designer) -> egui::Response {
+        let (response, painter) = ui.allocate_painter(ui.available_size(), Sense::click_and_drag());
+        let views = self.views(response.rect);
+        // all views have the same size, so the first one can be used when the position doesn't matter
+        let first_view = views[0].0;
+        if response.dragged() {
+            self.center -= response.drag_delta() / self.scale(first_view);
+        }
+        if response.double_clicked() {
+            self.reset_view();
+        }
+        if let Some(hover_pos) = response.hover_pos() {
+            let (scroll, zoom_delta) = ui.input(|i| (i.raw_scroll_delta.y, i.zoom_delta()));
+            let factor = zoom_delta * (scroll * Self::SCROLL_ZOOM_SPEED).exp();
+            if factor != 1. {
+                // keep the point under the cursor in place
+                let (view, _) = views
+                    .iter()
+                    .copied()
+                    .find(|(view, _)| view.contains(hover_pos))
+                    .unwrap_or(views[0]);
+                let anchor = self.to_design(view, hover_pos);
+                self.zoom =
+                    (self.zoom * factor).clamp(*Self::ZOOM_RANGE.start(), *Self::ZOOM_RANGE.end());
+                self.center = anchor - (hover_pos - view.center()) / self.scale(view);
+            }
+        }
+        let visible = Rect::from_min_max(
+            self.to_design(first_view, first_view.min),
+            self.to_design(first_view, first_view.max),
+        );
+        let region = if self.extrapolate {
+            visible
+        } else {
+            visible.intersect(UNIT_RECT)
+        };
+        if region.is_positive() {
+            let screen_size = region.size() * self.scale(first_view);
+            let (pixels_per_point, max_side) =
+                ui.input(|i| (i.pixels_per_point, i.max_texture_side));
+            let size = (
+                ((screen_size.x * pixels_per_point).round() as usize).clamp(1, max_side),
+                ((screen_size.y * pixels_per_point).round() as usize).clamp(1, max_side),
+            );
+            if !matches!(&self.rendered, Some(r) if r.size == size && r.region == region) {
+                self.render(ui.ctx(), designer, size, region);
+            }
+            if views.iter().any(|&(_, simulated)| simulated) {
+                self.simulate(ui.ctx());
+            }
+            if let Some(rendered) = &self.rendered {
+                for &(view, simulated) in &views {
+                    let texture = match (&rendered.simulated, simulated) {
+                        (Some((_, texture)), true) => texture,
+                        _ => &rendered.texture,
+                    };
+                    painter.with_clip_rect(view).image(
+                        texture.id(),
+                        Rect::from_min_max(
+                            self.to_screen(view, region.min),
+                            self.to_screen(view, region.max),
+                        ),
+                        UNIT_RECT,
+                        Color32::WHITE,
+                    );
+                }
+            }
+        }
+        let stroke = ui.visuals().widgets.noninteractive.fg_stroke;
+        for &(view, _) in &views {
+            if self.extrapolate {
+                painter.with_clip_rect(view).rect_stroke(
+                    Rect::from_min_max(
+                        self.to_screen(view, UNIT_RECT.min),
+                        self.to_screen(view, UNIT_RECT.max),
+                    ),
+                    0.,
+                    stroke,
+                );
+            }
+            if view.left() > response.rect.left() {
+                painter.vline(view.left(), view.y_range(), stroke);
+            }
+        }
+        response
+    }
+
+    fn render(
+        &mut self,
+        ctx: &egui::Context,
+        designer: &dyn Designer,
+        size: (usize, usize),
+        region: Rect,
+    ) {
+        // TODO don't create intermediate buffer somehow?
+        let mut buf = vec![Srgb::default(); size.0 * size.1];
+        designer.render(size, region, &mut buf);
+        let image = to_color_image(&buf, size);
+        match &mut self.rendered {
+            Some(rendered) => {
+                rendered.texture.set(image, egui::TextureOptions::default());
+                rendered.size = size;
+                rendered.region = region;
+                rendered.buf = buf;
+                rendered.simulated = None;
+            }
+            None => {
+                self.rendered = Some(Rendered {
+                    size,
+                    region,
+                    buf,
+                    texture: ctx.load_texture("gradient", image, egui::TextureOptions::default()),
+                    simulated: None,
+                });
+            }
+        }
+    }
+
+    /// Make sure the cvd simulated texture is up to date.
+    fn simulate(&mut self, ctx: &egui::Context) {
+        let Some(rendered) = &mut self.rendered else {
+            return;
+        };
+        if matches!(&rendered.simulated, Some((simulation, _)) if *simulation == self.simulation) {
+            return;
+        }
+        let simulated: Vec<_> = rendered
+            .buf
+            .par_iter()
+            .map(|&c| self.simulation.apply(c))
+            .collect();
+        let image = to_color_image(&simulated, rendered.size);
+        rendered.simulated = Some((
+            self.simulation,
+            ctx.load_texture("simulated", image, egui::TextureOptions::default()),
+        ));
+    }
+}
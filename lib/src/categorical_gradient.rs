@@ -0,0 +1,251 @@
+use std::f32::consts::TAU;
+
+use crate::{
+    cvd::{Deficiency, Simulation},
+    designer, gamut_mapping,
+    lab_ui::LabUi,
+    utils::{
+        delta_e_ok, oklab_to_srgb_clipped, range_sliders, render_par, resettable_slider,
+        NEUTRAL_LAB,
+    },
+};
+use eframe::emath::Rect;
+use palette::{convert::FromColorUnclamped, Oklab, Srgb};
+use strum::IntoEnumIterator;
+
+#[derive(PartialEq, Clone)]
+struct Params {
+    count: usize,
+    lightness: (f32, f32),
+    chroma: (f32, f32),
+    /// colors that are always part of the palette
+    locked: Vec<Oklab>,
+    /// also require the colors to be distinct under this deficiency
+    cvd: Option<Deficiency>,
+}
+
+/// A set of maximally distinct colors for charts, shown as swatches along x.
+#[derive(PartialEq, Clone)]
+pub struct Gradient {
+    params: Params,
+    colors: Vec<Oklab>,
+    /// smallest ΔEOK between any two colors
+    min_distance: f32,
+}
+
+/// A possible palette color, along with how it looks with a color vision deficiency.
+#[derive(Clone, Copy)]
+struct Candidate {
+    lab: Oklab,
+    simulated: Oklab,
+}
+
+impl Gradient {
+    const COUNT_DEFAULT: usize = 8;
+    const LIGHTNESS_DEFAULT: (f32, f32) = (0.45, 0.85);
+    const CHROMA_DEFAULT: (f32, f32) = (0.08, 0.25);
+    /// resolution of the grid of candidate colors
+    const L_STEPS: usize = 12;
+    const C_STEPS: usize = 6;
+    const H_STEPS: usize = 36;
+    /// passes of trying to move each color to a better candidate
+    const REFINE_PASSES: usize = 4;
+
+    pub fn new() -> Self {
+        let params = Params {
+            count: Self::COUNT_DEFAULT,
+            lightness: Self::LIGHTNESS_DEFAULT,
+            chroma: Self::CHROMA_DEFAULT,
+            locked: Vec::new(),
+            cvd: None,
+        };
+        let (colors, min_distance) = Self::optimize(&params);
+        Self {
+            params,
+            colors,
+            min_distance,
+        }
+    }
+
+    /// In gamut colors on an Oklch grid within the lightness and chroma bounds.
+    fn candidates(params: &Params) -> Vec<Oklab> {
+        let lerp = |(start, end): (f32, f32), i: usize, steps: usize| {
+            start + (end - start) * i as f32 / (steps - 1) as f32
+        };
+        let mut candidates = Vec::new();
+        for h in 0..Self::H_STEPS {
+            let (b, a) = (h as f32 / Self::H_STEPS as f32 * TAU).sin_cos();
+            for li in 0..Self::L_STEPS {
+                let l = lerp(params.lightness, li, Self::L_STEPS);
+                let max_chroma = gamut_mapping::max_chroma(l, a, b);
+                for ci in 0..Self::C_STEPS {
+                    let c = lerp(params.chroma, ci, Self::C_STEPS);
+                    if c <= max_chroma {
+                        candidates.push(Oklab::new(l, c * a, c * b));
+                    }
+                }
+            }
+        }
+        candidates
+    }
+
+    /// Pick colors that maximize the smallest distance between any two of them.
+    /// Greedily adds the farthest candidate, then repeatedly moves each unlocked color
+    /// to the candidate farthest from all others.
+    fn optimize(params: &Params) -> (Vec<Oklab>, f32) {
+        let simulation = params.cvd.map(|deficiency| Simulation {
+            deficiency,
+            severity: 1.,
+        });
+        let candidate = |lab: Oklab| Candidate {
+            lab,
+            simulated: match simulation {
+                Some(s) => {
+                    Oklab::from_color_unclamped(s.apply(oklab_to_srgb_clipped(lab)).into_linear())
+                }
+                None => lab,
+            },
+        };
+        let distance = |a: &Candidate, b: &Candidate| {
+            delta_e_ok(a.lab, b.lab).min(delta_e_ok(a.simulated, b.simulated))
+        };
+        let candidates: Vec<Candidate> = Self::candidates(params)
+            .into_iter()
+            .map(candidate)
+            .collect();
+        let mut chosen: Vec<Candidate> = params
+            .locked
+            .iter()
+            .take(params.count)
+            .map(|&lab| candidate(lab))
+            .collect();
+        let locked = chosen.len();
+        // distance to the closest chosen color, skipping index `skip`
+        let min_distance_to = |chosen: &[Candidate], c: &Candidate, skip: usize| {
+            chosen
+                .iter()
+                .enumerate()
+                .filter(|&(i, _)| i != skip)
+                .map(|(_, o)| distance(o, c))
+                .fold(f32::INFINITY, f32::min)
+        };
+        let farthest = |chosen: &[Candidate], skip: usize| {
+            candidates
+                .iter()
+                .map(|c| (c, min_distance_to(chosen, c, skip)))
+                .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        };
+        while chosen.len() < params.count {
+            let next = if chosen.is_empty() {
+                // start from the most saturated candidate
+                candidates
+                    .iter()
+                    .max_by(|a, b| (a.lab.a.hypot(a.lab.b)).total_cmp(&b.lab.a.hypot(b.lab.b)))
+                    .copied()
+            } else {
+                farthest(&chosen, usize::MAX).map(|(c, _)| *c)
+            };
+            let Some(next) = next else {
+                break;
+            };
+            chosen.push(next);
+        }
+        for _ in 0..Self::REFINE_PASSES {
+            let mut improved = false;
+            for i in locked..chosen.len() {
+                let current = min_distance_to(&chosen, &chosen[i], i);
+                if let Some((c, d)) = farthest(&chosen, i) {
+                    if d > current {
+                        chosen[i] = *c;
+                        improved = true;
+                    }
+                }
+            }
+            if !improved {
+                break;
+            }
+        }
+        let min_distance = (0..chosen.len())
+            .map(|i| min_distance_to(&chosen, &chosen[i], i))
+            .fold(f32::INFINITY, f32::min);
+        (chosen.into_iter().map(|c| c.lab).collect(), min_distance)
+    }
+}
+
+impl designer::Designer for Gradient {
+    fn show_ui(&mut self, ui: &mut eframe::egui::Ui) -> bool {
+        let mut c = self.clone();
+        let Params {
+            count,
+            lightness,
+            chroma,
+            locked,
+            cvd,
+        } = &mut c.params;
+        ui.vertical(|ui| {
+            resettable_slider(ui, count, "colors", 2..=16, Self::COUNT_DEFAULT);
+            range_sliders(
+                ui,
+                lightness,
+                ["L min", "L max"],
+                0. ..=1.,
+                Self::LIGHTNESS_DEFAULT,
+            );
+            range_sliders(
+                ui,
+                chroma,
+                ["C min", "C max"],
+                0. ..=0.4,
+                Self::CHROMA_DEFAULT,
+            );
+            ui.horizontal(|ui| {
+                ui.label("cvd safe");
+                ui.selectable_value(cvd, None, "off");
+                for deficiency in Deficiency::iter() {
+                    ui.selectable_value(cvd, Some(deficiency), format!("{:?}", deficiency));
+                }
+            })
+            .response
+            .on_hover_text("also keep the colors distinct for this color vision deficiency");
+            let mut remove = None;
+            for (i, lab) in locked.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.add(LabUi::new(lab, &format!("locked {}", i + 1)));
+                    if ui.button("🗑").clicked() {
+                        remove = Some(i);
+                    }
+                });
+            }
+            if let Some(i) = remove {
+                locked.remove(i);
+            }
+            if ui.button("+ lock a color").clicked() {
+                locked.push(NEUTRAL_LAB);
+            }
+        });
+        if c.params != self.params {
+            (c.colors, c.min_distance) = Self::optimize(&c.params);
+        }
+        ui.label(format!("min ΔEOK {:.4}", c.min_distance));
+        if c != *self {
+            *self = c;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn sample(&self, x: f32, _y: f32) -> Oklab {
+        let n = self.colors.len();
+        if n == 0 {
+            return NEUTRAL_LAB;
+        }
+        self.colors[((x.clamp(0., 1.) * n as f32) as usize).min(n - 1)]
+    }
+
+    fn render(&self, size: (usize, usize), region: Rect, buf: &mut [Srgb]) {
+        render_par(size, region, buf, |x, y| {
+            oklab_to_srgb_clipped(self.sample(x, y))
+        });
+    }
+}
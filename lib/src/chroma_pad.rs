@@ -0,0 +1,105 @@
+use std::{f32::consts::TAU, ops::RangeInclusive};
+
+use eframe::{
+    egui::{self, Sense, Widget},
+    epaint::{Color32, Mesh, Pos2, Shape, Stroke},
+};
+use palette::Oklab;
+
+use crate::{
+    gamut_mapping::max_chroma,
+    utils::{oklab_to_srgb_clipped, srgb_to_color32},
+};
+
+/// Drag a color around the a/b plane at its lightness, with the sRGB gamut shaded.
+pub struct ChromaPad<'a> {
+    lab: &'a mut Oklab,
+    a_range: RangeInclusive<f32>,
+    b_range: RangeInclusive<f32>,
+}
+
+impl<'a> ChromaPad<'a> {
+    const SIZE: f32 = 180.;
+    /// largest a and b shown
+    const EXTENT: f32 = 0.4;
+    const SEGMENTS: usize = 90;
+    /// rings between the center and the gamut boundary, so the shading follows chroma
+    const RINGS: usize = 4;
+
+    pub fn new(
+        lab: &'a mut Oklab,
+        a_range: RangeInclusive<f32>,
+        b_range: RangeInclusive<f32>,
+    ) -> Self {
+        Self {
+            lab,
+            a_range,
+            b_range,
+        }
+    }
+}
+
+impl<'a> Widget for ChromaPad<'a> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        let (mut response, painter) =
+            ui.allocate_painter(egui::vec2(Self::SIZE, Self::SIZE), Sense::click_and_drag());
+        let rect = response.rect;
+        let to_screen =
+            |a: f32, b: f32| rect.center() + egui::vec2(a, -b) * (rect.width() / 2. / Self::EXTENT);
+
+        if response.is_pointer_button_down_on() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                let d = ((pos - rect.center()) / (rect.width() / 2.) * Self::EXTENT).clamp(
+                    egui::Vec2::splat(-Self::EXTENT),
+                    egui::Vec2::splat(Self::EXTENT),
+                );
+                // stay within what the owner allows
+                self.lab.a = d.x.clamp(*self.a_range.start(), *self.a_range.end());
+                self.lab.b = (-d.y).clamp(*self.b_range.start(), *self.b_range.end());
+                response.mark_changed();
+            }
+        }
+
+        painter.rect_filled(rect, 2., ui.visuals().extreme_bg_color);
+        let l = self.lab.l;
+        // the gamut is star shaped around the gray axis, so a fan covers it
+        let boundary: Vec<(f32, f32, f32)> = (0..=Self::SEGMENTS)
+            .map(|i| {
+                let (b, a) = (i as f32 / Self::SEGMENTS as f32 * TAU).sin_cos();
+                (a, b, max_chroma(l, a, b))
+            })
+            .collect();
+        let mut mesh = Mesh::default();
+        for &(a, b, c) in &boundary {
+            for ring in 0..=Self::RINGS {
+                let c = c * ring as f32 / Self::RINGS as f32;
+                let color = oklab_to_srgb_clipped(Oklab::new(l, c * a, c * b));
+                mesh.colored_vertex(to_screen(c * a, c * b), srgb_to_color32(color));
+            }
+        }
+        let stride = (Self::RINGS + 1) as u32;
+        for i in 1..boundary.len() as u32 {
+            for ring in 1..stride {
+                let (v, prev) = (i * stride + ring, (i - 1) * stride + ring);
+                mesh.add_triangle(prev - 1, prev, v - 1);
+                mesh.add_triangle(prev, v - 1, v);
+            }
+        }
+        painter.add(mesh);
+        let axis = Stroke::new(1., ui.visuals().weak_text_color());
+        painter.line_segment([rect.center_top(), rect.center_bottom()], axis);
+        painter.line_segment([rect.left_center(), rect.right_center()], axis);
+        painter.add(Shape::closed_line(
+            boundary
+                .iter()
+                .map(|&(a, b, c)| to_screen(c * a, c * b))
+                .collect(),
+            Stroke::new(1., ui.visuals().text_color()),
+        ));
+
+        let pos: Pos2 = to_screen(self.lab.a, self.lab.b);
+        painter.circle_stroke(pos, 5., Stroke::new(3., Color32::BLACK));
+        painter.circle_stroke(pos, 5., Stroke::new(1.5, Color32::WHITE));
+        response
+    }
+}
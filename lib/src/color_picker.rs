@@ -0,0 +1,224 @@
+use std::f32::consts::TAU;
+
+use eframe::{
+    egui::{self, Sense, Widget},
+    epaint::{Color32, Mesh, Pos2, Stroke},
+};
+use palette::{FromColor, Oklab, Srgb};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use crate::{
+    okhsv::{okhsl_to_oklab, okhsv_to_oklab, oklab_to_okhsl, oklab_to_okhsv},
+    utils::{hex_to_srgb, oklab_to_srgb_clipped, srgb_to_color32, srgb_to_hex},
+};
+
+#[derive(EnumIter, Debug, PartialEq, Default, Copy, Clone)]
+enum Mode {
+    #[default]
+    Okhsv,
+    Okhsl,
+}
+
+impl Mode {
+    fn to_oklab(self, [h, s, x]: [f32; 3]) -> Oklab {
+        match self {
+            Mode::Okhsv => okhsv_to_oklab(h, s, x),
+            Mode::Okhsl => okhsl_to_oklab(h, s, x),
+        }
+    }
+
+    fn coordinates(self, lab: Oklab) -> [f32; 3] {
+        let (h, s, x) = match self {
+            Mode::Okhsv => oklab_to_okhsv(lab),
+            Mode::Okhsl => oklab_to_okhsl(lab),
+        };
+        [h, s.clamp(0., 1.), x.clamp(0., 1.)]
+    }
+}
+
+/// State kept between frames, so hue and saturation survive grays and black.
+#[derive(Clone, Default)]
+struct State {
+    mode: Mode,
+    /// the color the coordinates were last taken from or written to
+    lab: Option<Oklab>,
+    hsx: [f32; 3],
+    dragging_ring: bool,
+    /// the hex text while it is being edited
+    hex: String,
+}
+
+/// Okhsv/Okhsl picker with a hue ring, a saturation and value/lightness square, and text entry.
+pub struct ColorPicker<'a> {
+    lab: &'a mut Oklab,
+    id_source: egui::Id,
+}
+
+impl<'a> ColorPicker<'a> {
+    const SIZE: f32 = 180.;
+    const RING_WIDTH: f32 = 14.;
+    const SEGMENTS: usize = 96;
+    const GRID: usize = 16;
+
+    pub fn new(lab: &'a mut Oklab, id_source: impl std::hash::Hash) -> Self {
+        Self {
+            lab,
+            id_source: egui::Id::new(id_source),
+        }
+    }
+}
+
+fn color(mode: Mode, hsx: [f32; 3]) -> Color32 {
+    srgb_to_color32(oklab_to_srgb_clipped(mode.to_oklab(hsx)))
+}
+
+impl<'a> Widget for ColorPicker<'a> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        let id = ui.make_persistent_id(self.id_source);
+        let mut state: State = ui.data_mut(|d| d.get_temp(id)).unwrap_or_default();
+        if state.lab != Some(*self.lab) {
+            let old = state.hsx;
+            state.hsx = state.mode.coordinates(*self.lab);
+            // keep the hue where it is meaningless
+            if state.lab.is_some() && (state.hsx[1] < 1e-4 || state.hsx[2] < 1e-4) {
+                state.hsx[0] = old[0];
+            }
+        }
+        let mut changed = false;
+
+        let mut response = ui
+            .vertical(|ui| {
+                ui.horizontal(|ui| {
+                    for mode in Mode::iter() {
+                        if ui
+                            .selectable_value(&mut state.mode, mode, format!("{:?}", mode))
+                            .changed()
+                        {
+                            let old = state.hsx;
+                            state.hsx = mode.coordinates(*self.lab);
+                            if state.hsx[1] < 1e-4 || state.hsx[2] < 1e-4 {
+                                state.hsx[0] = old[0];
+                            }
+                        }
+                    }
+                });
+
+                let (response, painter) = ui
+                    .allocate_painter(egui::vec2(Self::SIZE, Self::SIZE), Sense::click_and_drag());
+                let rect = response.rect;
+                let center = rect.center();
+                let outer = Self::SIZE / 2.;
+                let inner = outer - Self::RING_WIDTH;
+                let half = (inner - 4.) / 2_f32.sqrt();
+                let square = egui::Rect::from_center_size(center, egui::vec2(2. * half, 2. * half));
+                let on_circle = |h: f32, r: f32| {
+                    let (sin, cos) = (h * TAU).sin_cos();
+                    center + egui::vec2(cos, -sin) * r
+                };
+
+                if response.is_pointer_button_down_on() {
+                    if let Some(pos) = response.interact_pointer_pos() {
+                        if ui.input(|i| i.pointer.any_pressed()) {
+                            state.dragging_ring = !square.expand(2.).contains(pos);
+                        }
+                        let hsx = &mut state.hsx;
+                        if state.dragging_ring {
+                            let d = pos - center;
+                            hsx[0] = ((-d.y).atan2(d.x) / TAU).rem_euclid(1.);
+                        } else {
+                            let t = (pos - square.min) / square.size();
+                            hsx[1] = t.x.clamp(0., 1.);
+                            hsx[2] = (1. - t.y).clamp(0., 1.);
+                        }
+                        changed = true;
+                    }
+                }
+
+                let mut mesh = Mesh::default();
+                for i in 0..=Self::SEGMENTS {
+                    let h = i as f32 / Self::SEGMENTS as f32;
+                    let c = color(Mode::Okhsv, [h, 1., 1.]);
+                    mesh.colored_vertex(on_circle(h, inner), c);
+                    mesh.colored_vertex(on_circle(h, outer), c);
+                    if i > 0 {
+                        let v = 2 * i as u32;
+                        mesh.add_triangle(v - 2, v - 1, v);
+                        mesh.add_triangle(v - 1, v, v + 1);
+                    }
+                }
+                let n = Self::GRID;
+                for j in 0..=n {
+                    for i in 0..=n {
+                        let (s, x) = (i as f32 / n as f32, 1. - j as f32 / n as f32);
+                        let pos = square.min + egui::vec2(s, 1. - x) * square.size();
+                        mesh.colored_vertex(pos, color(state.mode, [state.hsx[0], s, x]));
+                        if i > 0 && j > 0 {
+                            let base = 2 * (Self::SEGMENTS as u32 + 1);
+                            let v = base + (j * (n + 1) + i) as u32;
+                            let up = v - (n + 1) as u32;
+                            mesh.add_triangle(up - 1, up, v - 1);
+                            mesh.add_triangle(up, v - 1, v);
+                        }
+                    }
+                }
+                painter.add(mesh);
+
+                let [h, s, x] = state.hsx;
+                let marker = |pos: Pos2| {
+                    painter.circle_stroke(pos, 5., Stroke::new(3., Color32::BLACK));
+                    painter.circle_stroke(pos, 5., Stroke::new(1.5, Color32::WHITE));
+                };
+                marker(on_circle(h, (inner + outer) / 2.));
+                marker(square.min + egui::vec2(s, 1. - x) * square.size());
+
+                if changed {
+                    *self.lab = state.mode.to_oklab(state.hsx);
+                }
+
+                let rgb: Srgb<u8> = oklab_to_srgb_clipped(*self.lab).into_format();
+                ui.horizontal(|ui| {
+                    let (swatch, _) = ui.allocate_exact_size(egui::vec2(32., 18.), Sense::hover());
+                    ui.painter()
+                        .rect_filled(swatch, 2., srgb_to_color32(rgb.into_format()));
+
+                    let hex_id = id.with("hex");
+                    if !ui.memory(|m| m.has_focus(hex_id)) {
+                        state.hex = srgb_to_hex(rgb.into_format());
+                    }
+                    let edit = ui.add(
+                        egui::TextEdit::singleline(&mut state.hex)
+                            .id(hex_id)
+                            .desired_width(64.),
+                    );
+                    if edit.changed() {
+                        if let Some(srgb) = hex_to_srgb(&state.hex) {
+                            *self.lab = Oklab::from_color(srgb);
+                            state.hsx = state.mode.coordinates(*self.lab);
+                            changed = true;
+                        }
+                    }
+
+                    let mut components = [rgb.red, rgb.green, rgb.blue];
+                    let mut rgb_changed = false;
+                    for (c, label) in components.iter_mut().zip(["R ", "G ", "B "]) {
+                        rgb_changed |= ui.add(egui::DragValue::new(c).prefix(label)).changed();
+                    }
+                    if rgb_changed {
+                        let [r, g, b] = components;
+                        *self.lab = Oklab::from_color(Srgb::new(r, g, b).into_format::<f32>());
+                        state.hsx = state.mode.coordinates(*self.lab);
+                        changed = true;
+                    }
+                });
+            })
+            .response;
+
+        state.lab = Some(*self.lab);
+        ui.data_mut(|d| d.insert_temp(id, state));
+        if changed {
+            response.mark_changed();
+        }
+        response
+    }
+}
@@ -0,0 +1,362 @@
+//! Conversions between Oklab and other perceptual color spaces, as `Vec3`s.
+
+use glam::{vec3, Mat3, Vec3};
+use palette::{convert::FromColorUnclamped, Lab, Oklab, Xyz};
+use strum_macros::EnumIter;
+
+pub fn oklab_to_cielab(lab: Oklab) -> Vec3 {
+    let c = Lab::from_color_unclamped(lab);
+    vec3(c.l, c.a, c.b)
+}
+
+pub fn cielab_to_oklab(v: Vec3) -> Oklab {
+    Oklab::from_color_unclamped(Lab::new(v.x, v.y, v.z))
+}
+
+const JZ_M: Mat3 = Mat3::from_cols_array(&[
+    0.414_789_7,
+    -0.201_51,
+    -0.016_600_8,
+    0.579_999,
+    1.120_649,
+    0.2648,
+    0.014_648,
+    0.053_100_8,
+    0.668_479_9,
+]);
+const JZ_IAB: Mat3 = Mat3::from_cols_array(&[
+    0.5, 3.524, 0.199_076, 0.5, -4.066_708, 1.096_799, 0., 0.542_708, -1.295_875,
+]);
+/// inverses of `JZ_M` and `JZ_IAB`
+const JZ_M_INV: Mat3 = Mat3::from_cols_array(&[
+    1.924_226_5,
+    0.350_316_8,
+    -0.090_982_81,
+    -1.004_792_4,
+    0.726_481_2,
+    -0.312_728_3,
+    0.037_651_4,
+    -0.065_384_42,
+    1.522_766_6,
+]);
+const JZ_IAB_INV: Mat3 = Mat3::from_cols_array(&[
+    1.,
+    1.,
+    1.,
+    0.138_605_04,
+    -0.138_605_04,
+    -0.096_019_24,
+    0.058_047_32,
+    -0.058_047_32,
+    -0.811_891_9,
+]);
+const JZ_B: f32 = 1.15;
+const JZ_G: f32 = 0.66;
+const JZ_D: f32 = -0.56;
+const JZ_D0: f32 = 1.629_55e-11;
+/// constants of the PQ transfer function, with the exponent changed for JzAzBz
+const PQ_C1: f32 = 3424. / 4096.;
+const PQ_C2: f32 = 2413. / 128.;
+const PQ_C3: f32 = 2392. / 128.;
+const PQ_N: f32 = 2610. / 16384.;
+const PQ_P: f32 = 1.7 * 2523. / 32.;
+
+/// From XYZ in cd/m² to JzAzBz.
+/// See Safdar et al. 2017, "Perceptually uniform color space for image signals including
+/// high dynamic range and wide gamut".
+pub fn xyz_to_jzazbz(xyz: Vec3) -> Vec3 {
+    let xyz = vec3(
+        JZ_B * xyz.x - (JZ_B - 1.) * xyz.z,
+        JZ_G * xyz.y - (JZ_G - 1.) * xyz.x,
+        xyz.z,
+    );
+    let lms: Vec3 = (JZ_M * xyz)
+        .to_array()
+        .map(|x| {
+            let p = (x.max(0.) / 10000.).powf(PQ_N);
+            ((PQ_C1 + PQ_C2 * p) / (1. + PQ_C3 * p)).powf(PQ_P)
+        })
+        .into();
+    let iab = JZ_IAB * lms;
+    let jz = (1. + JZ_D) * iab.x / (1. + JZ_D * iab.x) - JZ_D0;
+    vec3(jz, iab.y, iab.z)
+}
+
+/// From JzAzBz to XYZ in cd/m².
+pub fn jzazbz_to_xyz(jab: Vec3) -> Vec3 {
+    let jz = jab.x + JZ_D0;
+    let iz = jz / (1. + JZ_D - JZ_D * jz);
+    let lms: Vec3 = (JZ_IAB_INV * vec3(iz, jab.y, jab.z))
+        .to_array()
+        .map(|x| {
+            let p = x.max(0.).powf(1. / PQ_P);
+            10000. * ((PQ_C1 - p) / (PQ_C3 * p - PQ_C2)).max(0.).powf(1. / PQ_N)
+        })
+        .into();
+    let xyz = JZ_M_INV * lms;
+    let x = (xyz.x + (JZ_B - 1.) * xyz.z) / JZ_B;
+    vec3(x, (xyz.y + (JZ_G - 1.) * x) / JZ_G, xyz.z)
+}
+
+pub fn oklab_to_xyz(lab: Oklab) -> Vec3 {
+    let xyz: Xyz = Xyz::from_color_unclamped(lab);
+    vec3(xyz.x, xyz.y, xyz.z)
+}
+
+pub fn xyz_to_oklab(xyz: Vec3) -> Oklab {
+    Oklab::from_color_unclamped(Xyz::new(xyz.x, xyz.y, xyz.z))
+}
+
+/// How the surroundings of the viewed colors affect CAM16.
+#[derive(EnumIter, Debug, PartialEq, Default, Copy, Clone)]
+pub enum Surround {
+    #[default]
+    Average,
+    Dim,
+    Dark,
+}
+
+impl Surround {
+    /// degree of adaptation factor F, impact of surround c and chromatic induction factor Nc
+    fn factors(self) -> (f32, f32, f32) {
+        match self {
+            Surround::Average => (1., 0.69, 1.),
+            Surround::Dim => (0.9, 0.59, 0.9),
+            Surround::Dark => (0.8, 0.525, 0.8),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct ViewingConditions {
+    /// luminance of the adapting field in cd/m²
+    pub adapting_luminance: f32,
+    /// relative luminance of the background, where white is 100
+    pub background_luminance: f32,
+    pub surround: Surround,
+}
+
+impl Default for ViewingConditions {
+    /// The conditions that sRGB assumes: 64 lux, a 20% gray background and an average surround.
+    fn default() -> Self {
+        Self {
+            adapting_luminance: 64. / std::f32::consts::PI * 0.2,
+            background_luminance: 20.,
+            surround: Surround::Average,
+        }
+    }
+}
+
+const M16: Mat3 = Mat3::from_cols_array(&[
+    0.401288, -0.250268, -0.002079, 0.650173, 1.204414, 0.048952, -0.051461, 0.045854, 0.953127,
+]);
+const M16_INV: Mat3 = Mat3::from_cols_array(&[
+    1.862_067_9,
+    0.387_526_54,
+    -0.015_841_5,
+    -1.011_254_6,
+    0.621_447_4,
+    -0.034_122_94,
+    0.149_186_78,
+    -0.008_973_985,
+    1.049_964_4,
+]);
+
+/// CAM16 with the viewing conditions already applied, converting to and from CAM16-UCS J'a'b'.
+/// See Li et al. 2017, "Comprehensive color solutions: CAM16, CAT16, and CAM16-UCS".
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Cam16 {
+    /// per channel chromatic adaptation factor
+    d_rgb: Vec3,
+    f_l: f32,
+    n: f32,
+    z: f32,
+    n_bb: f32,
+    c: f32,
+    n_c: f32,
+    /// achromatic response of white
+    a_w: f32,
+}
+
+impl Cam16 {
+    pub fn new(conditions: &ViewingConditions) -> Self {
+        // D65 with Y = 100
+        Self::with_white(conditions, vec3(95.047, 100., 108.883))
+    }
+
+    fn with_white(conditions: &ViewingConditions, white: Vec3) -> Self {
+        let (f, c, n_c) = conditions.surround.factors();
+        let l_a = conditions.adapting_luminance;
+        let k = 1. / (5. * l_a + 1.);
+        let k4 = k.powi(4);
+        let f_l = 0.2 * k4 * 5. * l_a + 0.1 * (1. - k4).powi(2) * (5. * l_a).cbrt();
+        let n = conditions.background_luminance / white.y;
+        let z = 1.48 + n.sqrt();
+        let n_bb = 0.725 * n.powf(-0.2);
+        let d = (f * (1. - 1. / 3.6 * ((-l_a - 42.) / 92.).exp())).clamp(0., 1.);
+        let rgb_w = M16 * white;
+        let d_rgb = d * white.y / rgb_w + Vec3::splat(1. - d);
+        let mut cam = Self {
+            d_rgb,
+            f_l,
+            n,
+            z,
+            n_bb,
+            c,
+            n_c,
+            a_w: 0.,
+        };
+        cam.a_w = cam.achromatic(cam.adapt(rgb_w));
+        cam
+    }
+
+    /// Cone responses after chromatic adaptation and compression.
+    fn adapt(&self, rgb: Vec3) -> Vec3 {
+        (self.d_rgb * rgb)
+            .to_array()
+            .map(|x| {
+                let p = (self.f_l * x.abs() / 100.).powf(0.42);
+                x.signum() * 400. * p / (p + 27.13) + 0.1
+            })
+            .into()
+    }
+
+    fn unadapt(&self, rgb_a: Vec3) -> Vec3 {
+        let rgb_c: Vec3 = rgb_a
+            .to_array()
+            .map(|x| {
+                let x = x - 0.1;
+                let x_abs = x.abs().min(399.999);
+                x.signum() * 100. / self.f_l * (27.13 * x_abs / (400. - x_abs)).powf(1. / 0.42)
+            })
+            .into();
+        rgb_c / self.d_rgb
+    }
+
+    fn achromatic(&self, rgb_a: Vec3) -> f32 {
+        (2. * rgb_a.x + rgb_a.y + rgb_a.z / 20. - 0.305) * self.n_bb
+    }
+
+    /// Eccentricity factor of the hue.
+    fn eccentricity(h: f32) -> f32 {
+        0.25 * ((h + 2.).cos() + 3.8)
+    }
+
+    /// From XYZ where white has Y = 1.
+    pub fn xyz_to_ucs(&self, xyz: Vec3) -> Vec3 {
+        let rgb_a = self.adapt(M16 * xyz * 100.);
+        let a = rgb_a.x - 12. * rgb_a.y / 11. + rgb_a.z / 11.;
+        let b = (rgb_a.x + rgb_a.y - 2. * rgb_a.z) / 9.;
+        let h = b.atan2(a);
+        let j = 100.
+            * (self.achromatic(rgb_a) / self.a_w)
+                .max(0.)
+                .powf(self.c * self.z);
+        let denominator = rgb_a.x + rgb_a.y + 21. / 20. * rgb_a.z;
+        let t = if denominator.abs() < 1e-9 {
+            0.
+        } else {
+            50000. / 13. * self.n_c * self.n_bb * Self::eccentricity(h) * a.hypot(b) / denominator
+        };
+        let chroma =
+            t.max(0.).powf(0.9) * (j / 100.).sqrt() * (1.64 - 0.29_f32.powf(self.n)).powf(0.73);
+        let m = chroma * self.f_l.powf(0.25);
+        let j_ucs = 1.7 * j / (1. + 0.007 * j);
+        let m_ucs = (1. + 0.0228 * m).ln() / 0.0228;
+        vec3(j_ucs, m_ucs * h.cos(), m_ucs * h.sin())
+    }
+
+    /// To XYZ where white has Y = 1.
+    pub fn ucs_to_xyz(&self, ucs: Vec3) -> Vec3 {
+        let j = (ucs.x / (1.7 - 0.007 * ucs.x)).max(0.);
+        let m_ucs = ucs.y.hypot(ucs.z);
+        let h = ucs.z.atan2(ucs.y);
+        let m = ((0.0228 * m_ucs).exp() - 1.) / 0.0228;
+        let chroma = m / self.f_l.powf(0.25);
+        let alpha = if j > 0. {
+            chroma / (j / 100.).sqrt()
+        } else {
+            0.
+        };
+        let t = (alpha / (1.64 - 0.29_f32.powf(self.n)).powf(0.73)).powf(1. / 0.9);
+        let p1 = 50000. / 13. * self.n_c * self.n_bb * Self::eccentricity(h);
+        let p2 = self.a_w * (j / 100.).powf(1. / (self.c * self.z)) / self.n_bb + 0.305;
+        let (sin, cos) = h.sin_cos();
+        let gamma = 23. * p2 * t / (23. * p1 + 11. * t * cos + 108. * t * sin);
+        let (a, b) = (gamma * cos, gamma * sin);
+        let rgb_a = vec3(
+            460. * p2 + 451. * a + 288. * b,
+            460. * p2 - 891. * a - 261. * b,
+            460. * p2 - 220. * a - 6300. * b,
+        ) / 1403.;
+        M16_INV * self.unadapt(rgb_a) / 100.
+    }
+
+    pub fn oklab_to_ucs(&self, lab: Oklab) -> Vec3 {
+        self.xyz_to_ucs(oklab_to_xyz(lab))
+    }
+
+    pub fn ucs_to_oklab(&self, ucs: Vec3) -> Oklab {
+        xyz_to_oklab(self.ucs_to_xyz(ucs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips() {
+        let cam = Cam16::new(&ViewingConditions::default());
+        for lab in [
+            Oklab::new(0.5, 0.1, -0.05),
+            Oklab::new(0.9, -0.05, 0.12),
+            Oklab::new(0.2, 0.02, 0.03),
+            Oklab::new(1., 0., 0.),
+        ] {
+            let back = cam.ucs_to_oklab(cam.oklab_to_ucs(lab));
+            assert!((back.l - lab.l).abs() < 1e-3, "{lab:?} {back:?}");
+            assert!((back.a - lab.a).abs() < 1e-3, "{lab:?} {back:?}");
+            assert!((back.b - lab.b).abs() < 1e-3, "{lab:?} {back:?}");
+            let back = cielab_to_oklab(oklab_to_cielab(lab));
+            assert!((back.l - lab.l).abs() < 1e-4);
+            let xyz = oklab_to_xyz(lab) * 203.;
+            let back = jzazbz_to_xyz(xyz_to_jzazbz(xyz));
+            assert!((back - xyz).abs().max_element() < 0.05, "{xyz} {back}");
+        }
+        // white has J = 100, which is J' ≈ 100 in the UCS,
+        // and only a little colorfulness since adaptation is incomplete at 4 cd/m²
+        let white = cam.oklab_to_ucs(Oklab::new(1., 0., 0.));
+        assert!((white.x - 100.).abs() < 0.5, "{white}");
+        assert!(white.y.hypot(white.z) < 3., "{white}");
+    }
+
+    #[test]
+    fn inverse_matrices() {
+        for (m, inv) in [(JZ_M, JZ_M_INV), (JZ_IAB, JZ_IAB_INV), (M16, M16_INV)] {
+            assert!((m * inv).abs_diff_eq(Mat3::IDENTITY, 1e-6), "{}", m * inv);
+        }
+    }
+
+    #[test]
+    fn matches_reference() {
+        // the CAM16 example of colour-science: J = 41.7312079, M = 0.1074367, h = 217.0679597,
+        // whose white is rounded differently from ours, which matters this close to gray
+        let cam = Cam16::with_white(
+            &ViewingConditions {
+                adapting_luminance: 318.31,
+                background_luminance: 20.,
+                surround: Surround::Average,
+            },
+            vec3(95.05, 100., 108.88),
+        );
+        let ucs = cam.xyz_to_ucs(vec3(19.01, 20., 21.78) / 100.);
+        let (j, m) = (41.731_21_f32, 0.107_436_7_f32);
+        let j_ucs = 1.7 * j / (1. + 0.007 * j);
+        let m_ucs = (1. + 0.0228 * m).ln() / 0.0228;
+        assert!((ucs.x - j_ucs).abs() < 0.01, "{ucs} {j_ucs}");
+        assert!((ucs.y.hypot(ucs.z) - m_ucs).abs() < 3e-4, "{ucs} {m_ucs}");
+        let h = ucs.z.atan2(ucs.y).to_degrees().rem_euclid(360.);
+        assert!((h - 217.067_96).abs() < 0.5, "{h}");
+    }
+}
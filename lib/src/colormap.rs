@@ -0,0 +1,213 @@
+use eframe::{
+    egui::{self, Sense},
+    emath::{vec2, Rect},
+};
+use palette::{Oklab, Srgb};
+
+use crate::{
+    designer::Designer,
+    utils::{
+        delta_e_ok, oklab_clipped, oklab_to_srgb_clipped, oklab_to_vec3, save_png, show_error,
+        srgb_to_color32, vec3_to_oklab,
+    },
+};
+
+/// A 1d colormap sampled from a design, with gamut mapping applied.
+#[derive(Clone)]
+pub struct Colormap {
+    colors: Vec<Oklab>,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Monotonicity {
+    Constant,
+    Increasing,
+    Decreasing,
+    /// number of times the direction changes
+    NonMonotonic(usize),
+}
+
+impl Colormap {
+    /// Sample `steps` colors along the x axis of the design at row `y`.
+    pub fn from_designer(designer: &dyn Designer, y: f32, steps: usize) -> Self {
+        debug_assert!(steps >= 2);
+        Self {
+            colors: (0..steps)
+                .map(|i| oklab_clipped(designer.sample(i as f32 / (steps - 1) as f32, y)))
+                .collect(),
+        }
+    }
+
+    /// Like [`Colormap::from_designer`], but with the colors spaced evenly in ΔEOK.
+    pub fn from_designer_linearized(designer: &dyn Designer, y: f32, steps: usize) -> Self {
+        // sample densely so the path is followed closely between the output steps
+        const SAMPLES: usize = 4096;
+        Self::from_designer(designer, y, SAMPLES.max(steps)).linearized(steps)
+    }
+
+    /// Re-parameterize the colormap by its cumulative ΔEOK,
+    /// so that equal steps along it give equal perceptual steps.
+    pub fn linearized(&self, steps: usize) -> Self {
+        debug_assert!(steps >= 2);
+        let lengths: Vec<f32> = std::iter::once(0.)
+            .chain(self.step_delta_e().into_iter().scan(0., |acc, d| {
+                *acc += d;
+                Some(*acc)
+            }))
+            .collect();
+        let total = *lengths.last().unwrap();
+        if total <= 0. {
+            return Self {
+                colors: vec![self.colors[0]; steps],
+            };
+        }
+        Self {
+            colors: (0..steps)
+                .map(|i| {
+                    let d = total * i as f32 / (steps - 1) as f32;
+                    let segment = lengths
+                        .partition_point(|&l| l <= d)
+                        .clamp(1, lengths.len() - 1);
+                    let (start, end) = (lengths[segment - 1], lengths[segment]);
+                    let f = if end > start {
+                        ((d - start) / (end - start)).clamp(0., 1.)
+                    } else {
+                        0.
+                    };
+                    vec3_to_oklab(
+                        oklab_to_vec3(self.colors[segment - 1])
+                            .lerp(oklab_to_vec3(self.colors[segment]), f),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    pub fn colors(&self) -> &[Oklab] {
+        &self.colors
+    }
+
+    pub fn srgb(&self) -> Vec<Srgb> {
+        self.colors
+            .iter()
+            .map(|&c| oklab_to_srgb_clipped(c))
+            .collect()
+    }
+
+    /// Save as an image with one pixel per color.
+    pub fn save_png<P: AsRef<std::path::Path>>(&self, path: P) {
+        save_png(&self.srgb(), (self.colors.len(), 1), path);
+    }
+
+    /// Save as comma separated sRGB values in `0..=1`, one color per line.
+    pub fn save_csv<P: AsRef<std::path::Path>>(&self, path: P) {
+        let csv: String = self
+            .srgb()
+            .iter()
+            .map(|c| format!("{:.6},{:.6},{:.6}\n", c.red, c.green, c.blue))
+            .collect();
+        if let Err(e) = std::fs::write(path, csv) {
+            show_error("Error saving colormap", &e);
+        }
+    }
+
+    /// Linearly interpolated color at `t` in `0..=1`.
+    pub fn lookup(&self, t: f32) -> Oklab {
+        let f = t.clamp(0., 1.) * (self.colors.len() - 1) as f32;
+        let i = (f as usize).min(self.colors.len() - 2);
+        vec3_to_oklab(
+            oklab_to_vec3(self.colors[i]).lerp(oklab_to_vec3(self.colors[i + 1]), f - i as f32),
+        )
+    }
+
+    /// ΔEOK between each pair of neighbouring colors.
+    pub fn step_delta_e(&self) -> Vec<f32> {
+        self.colors
+            .windows(2)
+            .map(|w| delta_e_ok(w[0], w[1]))
+            .collect()
+    }
+
+    pub fn lightness_monotonicity(&self) -> Monotonicity {
+        const EPS: f32 = 1e-5;
+        let mut direction = 0.;
+        let mut changes = 0;
+        for w in self.colors.windows(2) {
+            let d = w[1].l - w[0].l;
+            if d.abs() < EPS {
+                continue;
+            }
+            if direction != 0. && d.signum() != direction {
+                changes += 1;
+            }
+            direction = d.signum();
+        }
+        match changes {
+            0 if direction == 0. => Monotonicity::Constant,
+            0 if direction > 0. => Monotonicity::Increasing,
+            0 => Monotonicity::Decreasing,
+            n => Monotonicity::NonMonotonic(n),
+        }
+    }
+
+    /// Kovesi's test image for colormaps: a ramp with a superimposed sine wave whose amplitude
+    /// decreases from the top to the bottom of the image.
+    /// See <https://arxiv.org/abs/1509.03700>
+    pub fn sine_ramp(&self, size: [usize; 2]) -> egui::ColorImage {
+        const AMPLITUDE: f32 = 12.5 / 255.;
+        const WAVELENGTH: f32 = 8.;
+        const POWER: i32 = 2;
+        let [w, h] = size;
+        let pixels = (0..h)
+            .flat_map(|y| {
+                let modulation = ((h - 1 - y) as f32 / (h - 1) as f32).powi(POWER);
+                (0..w).map(move |x| {
+                    let ramp = x as f32 / (w - 1) as f32;
+                    let sine = (x as f32 * std::f32::consts::TAU / WAVELENGTH).sin();
+                    let t =
+                        AMPLITUDE + ramp * (1. - 2. * AMPLITUDE) + AMPLITUDE * sine * modulation;
+                    srgb_to_color32(oklab_to_srgb_clipped(self.lookup(t)))
+                })
+            })
+            .collect();
+        egui::ColorImage { size, pixels }
+    }
+}
+
+/// Paint a horizontal strip of colors.
+pub fn strip(ui: &mut egui::Ui, colors: &[Oklab], height: f32) -> egui::Response {
+    let (response, painter) =
+        ui.allocate_painter(vec2(ui.available_width(), height), Sense::hover());
+    let rect = response.rect;
+    let w = rect.width() / colors.len() as f32;
+    for (i, &lab) in colors.iter().enumerate() {
+        let x = rect.left() + i as f32 * w;
+        painter.rect_filled(
+            // overlap slightly to avoid seams
+            Rect::from_x_y_ranges(x..=x + w + 0.5, rect.y_range()),
+            0.,
+            srgb_to_color32(oklab_to_srgb_clipped(lab)),
+        );
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn linearized_steps_are_even() {
+        // a ramp that moves slowly at first and then speeds up
+        let colormap = Colormap {
+            colors: (0..100)
+                .map(|i| Oklab::new((i as f32 / 99.).powi(3), 0., 0.))
+                .collect(),
+        };
+        let steps = colormap.linearized(11).step_delta_e();
+        assert_eq!(steps.len(), 10);
+        for d in steps {
+            assert!((d - 0.1).abs() < 0.001, "{d}");
+        }
+    }
+}
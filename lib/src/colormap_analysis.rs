@@ -0,0 +1,152 @@
+use eframe::{egui, epaint::Color32};
+use native_dialog::FileDialog;
+
+use crate::{
+    colormap::{self, Colormap, Monotonicity},
+    designer::Designer,
+    plot::LinePlot,
+    utils::resettable_slider,
+};
+
+struct Analysis {
+    colormap: Colormap,
+    delta_e: Vec<f32>,
+    lightness: Vec<f32>,
+    monotonicity: Monotonicity,
+    sine_ramp: egui::TextureHandle,
+}
+
+/// Perceptual uniformity analysis of the design, treated as a 1d colormap along its x axis.
+pub struct ColormapAnalysis {
+    pub open: bool,
+    y: f32,
+    steps: usize,
+    /// space the colors evenly in ΔEOK
+    linearize: bool,
+    analysis: Option<Analysis>,
+}
+
+impl ColormapAnalysis {
+    const Y_DEFAULT: f32 = 0.5;
+    const STEPS_DEFAULT: usize = 256;
+    /// a whole number of periods of the sine wave
+    const SINE_RAMP_SIZE: [usize; 2] = [512, 128];
+
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            y: Self::Y_DEFAULT,
+            steps: Self::STEPS_DEFAULT,
+            linearize: false,
+            analysis: None,
+        }
+    }
+
+    pub fn invalidate(&mut self) {
+        self.analysis = None;
+    }
+
+    pub fn show_window(&mut self, ctx: &egui::Context, designer: &dyn Designer) {
+        let mut open = self.open;
+        egui::Window::new("colormap analysis")
+            .open(&mut open)
+            .default_width(Self::SINE_RAMP_SIZE[0] as f32)
+            .show(ctx, |ui| self.ui(ui, designer));
+        self.open = open;
+    }
+
+    fn analyze(&self, ctx: &egui::Context, designer: &dyn Designer) -> Analysis {
+        let colormap = if self.linearize {
+            Colormap::from_designer_linearized(designer, self.y, self.steps)
+        } else {
+            Colormap::from_designer(designer, self.y, self.steps)
+        };
+        Analysis {
+            delta_e: colormap.step_delta_e(),
+            lightness: colormap.colors().iter().map(|c| c.l).collect(),
+            monotonicity: colormap.lightness_monotonicity(),
+            sine_ramp: ctx.load_texture(
+                "sine ramp",
+                colormap.sine_ramp(Self::SINE_RAMP_SIZE),
+                egui::TextureOptions::NEAREST,
+            ),
+            colormap,
+        }
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, designer: &dyn Designer) {
+        let params = (self.y, self.steps, self.linearize);
+        resettable_slider(ui, &mut self.y, "y", 0. ..=1., Self::Y_DEFAULT);
+        resettable_slider(ui, &mut self.steps, "steps", 2..=1024, Self::STEPS_DEFAULT);
+        ui.checkbox(&mut self.linearize, "linearize")
+            .on_hover_text("re-parameterize so that all steps are equal in ΔEOK");
+        if self.analysis.is_none() || params != (self.y, self.steps, self.linearize) {
+            self.analysis = Some(self.analyze(ui.ctx(), designer));
+        }
+        let analysis = self.analysis.as_ref().unwrap();
+        ui.horizontal(|ui| {
+            if ui.button("💾 png").clicked() {
+                if let Ok(Some(path)) = FileDialog::new()
+                    .add_filter("PNG Image", &["png"])
+                    .show_save_single_file()
+                {
+                    analysis.colormap.save_png(path);
+                }
+            }
+            if ui.button("💾 csv").clicked() {
+                if let Ok(Some(path)) = FileDialog::new()
+                    .add_filter("CSV", &["csv"])
+                    .show_save_single_file()
+                {
+                    analysis.colormap.save_csv(path);
+                }
+            }
+        });
+        let line_color = ui.visuals().text_color();
+        colormap::strip(ui, analysis.colormap.colors(), 24.);
+        ui.add(LinePlot::new("ΔEOK per step").series(&analysis.delta_e, line_color));
+        let (min, max, sum) = analysis.delta_e.iter().fold(
+            (f32::INFINITY, f32::NEG_INFINITY, 0.),
+            |(min, max, sum), &d| (min.min(d), max.max(d), sum + d),
+        );
+        let mean = sum / analysis.delta_e.len() as f32;
+        egui::Grid::new("delta e stats").show(ui, |ui| {
+            ui.label("total ΔEOK");
+            ui.monospace(format!("{sum:.4}"));
+            ui.end_row();
+            ui.label("mean step");
+            ui.monospace(format!("{mean:.5}"));
+            ui.end_row();
+            ui.label("min / max step");
+            ui.monospace(format!("{min:.5} / {max:.5}"));
+            ui.end_row();
+            ui.label("uniformity")
+                .on_hover_text("smallest step divided by largest step");
+            ui.monospace(format!("{:.1}%", 100. * min / max.max(f32::EPSILON)));
+            ui.end_row();
+        });
+        ui.add(
+            LinePlot::new("L")
+                .y_range(0. ..=1.)
+                .series(&analysis.lightness, line_color),
+        );
+        match analysis.monotonicity {
+            Monotonicity::Constant => ui.label("lightness is constant"),
+            Monotonicity::Increasing => ui.label("lightness is monotonically increasing"),
+            Monotonicity::Decreasing => ui.label("lightness is monotonically decreasing"),
+            Monotonicity::NonMonotonic(n) => ui.colored_label(
+                Color32::LIGHT_RED,
+                format!("lightness is not monotonic, it changes direction {n} times"),
+            ),
+        };
+        ui.label("sine ramp");
+        let texture = &analysis.sine_ramp;
+        egui::ScrollArea::horizontal().show(ui, |ui| {
+            // show at native resolution since the test pattern is only a few pixels wide
+            ui.image((
+                texture.id(),
+                texture.size_vec2() / ui.ctx().pixels_per_point(),
+            ));
+        });
+    }
+}
@@ -0,0 +1,387 @@
+use eframe::{
+    egui::{self, Painter, Sense},
+    emath::{pos2, vec2, Pos2, Rect},
+    epaint::{Color32, Stroke},
+};
+use palette::Srgb;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use crate::{
+    canvas::Canvas,
+    designer::Designer,
+    utils::{oklab_to_srgb_clipped, resettable_slider, srgb_to_color32, srgb_to_hex, UNIT_RECT},
+};
+
+/// WCAG 2.x relative luminance.
+pub fn relative_luminance(rgb: Srgb) -> f32 {
+    let linear = rgb.into_linear();
+    0.2126 * linear.red + 0.7152 * linear.green + 0.0722 * linear.blue
+}
+
+/// WCAG 2.x contrast ratio, from 1 to 21, independent of which color is the text.
+pub fn wcag_ratio(a: Srgb, b: Srgb) -> f32 {
+    let (a, b) = (relative_luminance(a), relative_luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+/// APCA lightness contrast Lc (0.0.98G-4g constants), about -108..106.
+/// Positive for dark text on a light background, negative for light text on a dark one.
+pub fn apca_lc(text: Srgb, background: Srgb) -> f32 {
+    const BLACK_THRESHOLD: f32 = 0.022;
+    const BLACK_CLAMP: f32 = 1.414;
+    const SCALE: f32 = 1.14;
+    const OFFSET: f32 = 0.027;
+    const LOW_CLIP: f32 = 0.1;
+    const DELTA_Y_MIN: f32 = 0.0005;
+    // APCA uses a simple power curve instead of the piecewise sRGB transfer function
+    let luminance = |rgb: Srgb| {
+        let y = 0.212_672_9 * rgb.red.powf(2.4)
+            + 0.715_152_2 * rgb.green.powf(2.4)
+            + 0.072_175 * rgb.blue.powf(2.4);
+        if y < BLACK_THRESHOLD {
+            y + (BLACK_THRESHOLD - y).powf(BLACK_CLAMP)
+        } else {
+            y
+        }
+    };
+    let (text, background) = (luminance(text), luminance(background));
+    if (background - text).abs() < DELTA_Y_MIN {
+        return 0.;
+    }
+    let lc = if background > text {
+        let s = (background.powf(0.56) - text.powf(0.57)) * SCALE;
+        if s < LOW_CLIP {
+            0.
+        } else {
+            s - OFFSET
+        }
+    } else {
+        let s = (background.powf(0.65) - text.powf(0.62)) * SCALE;
+        if s > -LOW_CLIP {
+            0.
+        } else {
+            s + OFFSET
+        }
+    };
+    lc * 100.
+}
+
+#[derive(EnumIter, Debug, PartialEq, Default, Copy, Clone)]
+enum Metric {
+    #[default]
+    Wcag,
+    Apca,
+}
+
+impl Metric {
+    fn target_range(self) -> std::ops::RangeInclusive<f32> {
+        match self {
+            Metric::Wcag => 1. ..=21.,
+            Metric::Apca => 0. ..=106.,
+        }
+    }
+
+    fn target_default(self) -> f32 {
+        match self {
+            Metric::Wcag => 4.5,
+            Metric::Apca => 60.,
+        }
+    }
+
+    /// The contrast of text on a background in this metric, larger is better.
+    fn contrast(self, text: Srgb, background: Srgb) -> f32 {
+        match self {
+            Metric::Wcag => wcag_ratio(text, background),
+            Metric::Apca => apca_lc(text, background).abs(),
+        }
+    }
+}
+
+/// Contrast between pairs of colors of the design,
+/// and an overlay marking where the design has too little contrast against a text color.
+pub struct Contrast {
+    pub open: bool,
+    /// points of the design used as text and background
+    pair: [Option<Pos2>; 2],
+    /// the pair member that the next click on the preview sets
+    next: usize,
+    metric: Metric,
+    target: f32,
+    text_color: [u8; 3],
+    show_overlay: bool,
+    swatches: usize,
+    /// y of the row of swatches
+    y: f32,
+    /// stripes over the parts of the render that fail the target,
+    /// along with the render size and region and the settings it was made for
+    overlay: Option<(OverlayKey, egui::TextureHandle)>,
+}
+
+#[derive(PartialEq, Clone, Copy)]
+struct OverlayKey {
+    size: (usize, usize),
+    region: Rect,
+    metric: Metric,
+    target: f32,
+    text_color: [u8; 3],
+}
+
+impl Contrast {
+    const SWATCHES_DEFAULT: usize = 8;
+    const Y_DEFAULT: f32 = 0.5;
+    const SWATCH_SIZE: f32 = 28.;
+    /// width of the overlay stripes, in pixels
+    const STRIPE: usize = 6;
+    const LABELS: [&'static str; 2] = ["text", "background"];
+
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            pair: [None, None],
+            next: 0,
+            metric: Metric::default(),
+            target: Metric::default().target_default(),
+            text_color: [255, 255, 255],
+            show_overlay: false,
+            swatches: Self::SWATCHES_DEFAULT,
+            y: Self::Y_DEFAULT,
+            overlay: None,
+        }
+    }
+
+    pub fn invalidate(&mut self) {
+        self.overlay = None;
+    }
+
+    fn text_srgb(&self) -> Srgb {
+        Srgb::new(self.text_color[0], self.text_color[1], self.text_color[2]).into_format()
+    }
+
+    /// The color drawn at `p`, so the numbers match the preview and the overlay.
+    /// Points outside of the current render fall back to sampling the design.
+    fn color_at(canvas: &Canvas, designer: &dyn Designer, p: Pos2) -> Srgb {
+        canvas
+            .rendered_color(p)
+            .unwrap_or_else(|| oklab_to_srgb_clipped(designer.sample(p.x, p.y)))
+    }
+
+    /// Make sure the overlay matches the current render of the canvas.
+    fn update_overlay(&mut self, ctx: &egui::Context, canvas: &Canvas) {
+        let Some((buf, size, region)) = canvas.rendered() else {
+            self.overlay = None;
+            return;
+        };
+        let key = OverlayKey {
+            size,
+            region,
+            metric: self.metric,
+            target: self.target,
+            text_color: self.text_color,
+        };
+        if matches!(&self.overlay, Some((k, _)) if *k == key) {
+            return;
+        }
+        let text = self.text_srgb();
+        let pixels: Vec<Color32> = buf
+            .par_iter()
+            .enumerate()
+            .map(|(i, &background)| {
+                if self.metric.contrast(text, background) >= self.target {
+                    Color32::TRANSPARENT
+                } else if (i % size.0 + i / size.0) / Self::STRIPE % 2 == 0 {
+                    Color32::from_black_alpha(160)
+                } else {
+                    Color32::from_white_alpha(160)
+                }
+            })
+            .collect();
+        let image = egui::ColorImage {
+            size: [size.0, size.1],
+            pixels,
+        };
+        self.overlay = Some((
+            key,
+            ctx.load_texture("contrast overlay", image, egui::TextureOptions::NEAREST),
+        ));
+    }
+
+    /// Handle clicks on the canvas and draw the overlay and the picked points on top of it.
+    pub fn show_on_canvas(
+        &mut self,
+        canvas: &Canvas,
+        response: &egui::Response,
+        painter: &Painter,
+        editing: bool,
+    ) {
+        if editing && response.clicked() {
+            if let Some(pos) = canvas.hover_pos(response) {
+                self.pair[self.next] = Some(pos);
+                self.next = 1 - self.next;
+                self.open = true;
+            }
+        }
+        if self.show_overlay {
+            self.update_overlay(&response.ctx, canvas);
+        }
+        for view in canvas.view_rects(response) {
+            let painter = painter.with_clip_rect(view);
+            if let (true, Some((key, texture))) = (self.show_overlay, &self.overlay) {
+                painter.image(
+                    texture.id(),
+                    Rect::from_min_max(
+                        canvas.to_screen(view, key.region.min),
+                        canvas.to_screen(view, key.region.max),
+                    ),
+                    UNIT_RECT,
+                    Color32::WHITE,
+                );
+            }
+            for (p, label) in self.pair.iter().zip(Self::LABELS) {
+                if let Some(p) = p {
+                    let p = canvas.to_screen(view, *p);
+                    painter.circle(p, 4., Color32::WHITE, Stroke::new(1.5, Color32::BLACK));
+                    painter.text(
+                        p + vec2(6., -6.),
+                        egui::Align2::LEFT_BOTTOM,
+                        label,
+                        egui::FontId::proportional(12.),
+                        Color32::WHITE,
+                    );
+                }
+            }
+        }
+    }
+
+    pub fn show_window(&mut self, ctx: &egui::Context, canvas: &Canvas, designer: &dyn Designer) {
+        let mut open = self.open;
+        egui::Window::new("contrast")
+            .open(&mut open)
+            .default_width(300.)
+            .show(ctx, |ui| self.ui(ui, canvas, designer));
+        self.open = open;
+    }
+
+    fn swatch(ui: &mut egui::Ui, rgb: Srgb) -> egui::Response {
+        let (rect, response) =
+            ui.allocate_exact_size(vec2(Self::SWATCH_SIZE, Self::SWATCH_SIZE), Sense::click());
+        ui.painter().rect_filled(rect, 0., srgb_to_color32(rgb));
+        response
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, canvas: &Canvas, designer: &dyn Designer) {
+        ui.horizontal(|ui| {
+            for (p, label) in self.pair.iter().zip(Self::LABELS) {
+                match p {
+                    Some(p) => {
+                        let rgb = Self::color_at(canvas, designer, *p);
+                        Self::swatch(ui, rgb).on_hover_text(srgb_to_hex(rgb));
+                    }
+                    None => {
+                        ui.allocate_exact_size(
+                            vec2(Self::SWATCH_SIZE, Self::SWATCH_SIZE),
+                            Sense::hover(),
+                        );
+                    }
+                }
+                ui.label(label);
+            }
+            if ui.button("⇄").on_hover_text("swap").clicked() {
+                self.pair.swap(0, 1);
+            }
+        });
+        if let [Some(text), Some(background)] = self.pair {
+            let (text, background) = (
+                Self::color_at(canvas, designer, text),
+                Self::color_at(canvas, designer, background),
+            );
+            let ratio = wcag_ratio(text, background);
+            let level = if ratio >= 7. {
+                "AAA"
+            } else if ratio >= 4.5 {
+                "AA"
+            } else if ratio >= 3. {
+                "AA large text"
+            } else {
+                "fail"
+            };
+            egui::Grid::new("contrast results").show(ui, |ui| {
+                ui.label("WCAG 2");
+                ui.monospace(format!("{ratio:.2}:1"));
+                ui.label(level);
+                ui.end_row();
+                ui.label("APCA");
+                ui.monospace(format!("Lc {:.1}", apca_lc(text, background)));
+                ui.end_row();
+            });
+        } else {
+            ui.label("click on the preview using the contrast tool, or on the swatches below");
+        }
+        ui.separator();
+        resettable_slider(
+            ui,
+            &mut self.swatches,
+            "swatches",
+            2..=16,
+            Self::SWATCHES_DEFAULT,
+        );
+        resettable_slider(ui, &mut self.y, "y", 0. ..=1., Self::Y_DEFAULT);
+        ui.horizontal(|ui| {
+            ui.spacing_mut().item_spacing.x = 2.;
+            for i in 0..self.swatches {
+                let p = pos2((i as f32 + 0.5) / self.swatches as f32, self.y);
+                let rgb = Self::color_at(canvas, designer, p);
+                let response = Self::swatch(ui, rgb).on_hover_text(format!(
+                    "{}\nclick for text, right click for background",
+                    srgb_to_hex(rgb)
+                ));
+                if response.clicked() {
+                    self.pair[0] = Some(p);
+                }
+                if response.secondary_clicked() {
+                    self.pair[1] = Some(p);
+                }
+            }
+        });
+        ui.separator();
+        ui.checkbox(&mut self.show_overlay, "show low contrast on the preview");
+        ui.horizontal(|ui| {
+            ui.color_edit_button_srgb(&mut self.text_color);
+            ui.label("text color");
+        });
+        ui.horizontal(|ui| {
+            let metric = self.metric;
+            for m in Metric::iter() {
+                ui.selectable_value(&mut self.metric, m, format!("{:?}", m));
+            }
+            if self.metric != metric {
+                self.target = self.metric.target_default();
+            }
+        });
+        resettable_slider(
+            ui,
+            &mut self.target,
+            "target",
+            self.metric.target_range(),
+            self.metric.target_default(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reference_values() {
+        let black = Srgb::new(0., 0., 0.);
+        let white = Srgb::new(1., 1., 1.);
+        assert!((wcag_ratio(black, white) - 21.).abs() < 0.01);
+        assert!((wcag_ratio(white, white) - 1.).abs() < 0.01);
+        assert!((apca_lc(black, white) - 106.04).abs() < 0.1);
+        assert!((apca_lc(white, black) + 107.88).abs() < 0.1);
+        let gray: Srgb = Srgb::new(0x88u8, 0x88, 0x88).into_format();
+        assert!((wcag_ratio(gray, white) - 3.54).abs() < 0.01);
+    }
+}
@@ -0,0 +1,180 @@
+use crate::{
+    designer,
+    utils::{oklab_to_srgb, oklab_to_srgb_clipped, render_par, resettable_slider, smooth_par},
+};
+use eframe::emath::Rect;
+use palette::{convert::FromColorUnclamped, Clamp, Oklab, Srgb};
+use std::f32::consts::PI;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+#[derive(EnumIter, Debug, PartialEq, Default, Copy, Clone)]
+enum Formulation {
+    /// a helix around the gray axis of Oklab
+    #[default]
+    Oklab,
+    /// Green's original helix around the diagonal of the sRGB cube
+    Rgb,
+}
+
+/// Cubehelix along x: lightness increases while the hue rotates around the gray axis,
+/// with the most saturation in the midtones. The hue also varies along y.
+/// See <https://arxiv.org/abs/1108.5083>
+#[derive(PartialEq, Clone)]
+pub struct Gradient {
+    formulation: Formulation,
+    /// hue at the start, in turns of the helix
+    start: f32,
+    /// turns of the helix from start to end
+    rotations: f32,
+    /// hue change from top to bottom
+    hue_variation: f32,
+    saturation: f32,
+    gamma: f32,
+    extend: bool,
+    smooth: f32,
+}
+
+impl Gradient {
+    const START_DEFAULT: f32 = 0.5 / 3.;
+    const ROTATIONS_DEFAULT: f32 = -1.5;
+    const HUE_VARIATION_DEFAULT: f32 = 0.;
+    const SATURATION_DEFAULT: f32 = 1.;
+    const GAMMA_DEFAULT: f32 = 1.;
+    const SMOOTH_DEFAULT: f32 = 0.;
+    /// Oklab chroma at mid lightness for a saturation of 1
+    const OKLAB_CHROMA: f32 = 0.2;
+
+    pub fn new() -> Self {
+        Self {
+            formulation: Formulation::default(),
+            start: Self::START_DEFAULT,
+            rotations: Self::ROTATIONS_DEFAULT,
+            hue_variation: Self::HUE_VARIATION_DEFAULT,
+            saturation: Self::SATURATION_DEFAULT,
+            gamma: Self::GAMMA_DEFAULT,
+            extend: true,
+            smooth: Self::SMOOTH_DEFAULT,
+        }
+    }
+}
+
+impl designer::Designer for Gradient {
+    fn show_ui(&mut self, ui: &mut eframe::egui::Ui) -> bool {
+        let mut c = self.clone();
+        let Gradient {
+            formulation,
+            start,
+            rotations,
+            hue_variation,
+            saturation,
+            gamma,
+            extend,
+            smooth,
+        } = &mut c;
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                for f in Formulation::iter() {
+                    ui.selectable_value(formulation, f, format!("{:?}", f));
+                }
+            });
+            resettable_slider(ui, start, "start", 0. ..=1., Self::START_DEFAULT);
+            resettable_slider(
+                ui,
+                rotations,
+                "rotations",
+                -3. ..=3.,
+                Self::ROTATIONS_DEFAULT,
+            );
+            resettable_slider(
+                ui,
+                hue_variation,
+                "hue variation",
+                -PI * 2.0..=PI * 2.,
+                Self::HUE_VARIATION_DEFAULT,
+            );
+            resettable_slider(
+                ui,
+                saturation,
+                "saturation",
+                0. ..=3.,
+                Self::SATURATION_DEFAULT,
+            );
+            resettable_slider(ui, gamma, "gamma", 0.2..=3., Self::GAMMA_DEFAULT);
+            ui.checkbox(extend, "extend");
+            resettable_slider(ui, smooth, "smooth", 0. ..=100., Self::SMOOTH_DEFAULT);
+        });
+        if c != *self {
+            *self = c;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn sample(&self, x: f32, y: f32) -> Oklab {
+        let f = x.clamp(0., 1.).powf(self.gamma);
+        let angle = 2. * PI * (self.start + self.rotations * x) + self.hue_variation * (y - 0.5);
+        let (sin, cos) = angle.sin_cos();
+        // largest in the middle and zero at black and white
+        let amplitude = self.saturation * f * (1. - f);
+        match self.formulation {
+            Formulation::Oklab => {
+                let chroma = amplitude * 4. * Self::OKLAB_CHROMA;
+                Oklab::new(f, chroma * cos, chroma * sin)
+            }
+            Formulation::Rgb => {
+                let amplitude = amplitude / 2.;
+                let rgb = Srgb::new(
+                    f + amplitude * (-0.14861 * cos + 1.78277 * sin),
+                    f + amplitude * (-0.29227 * cos - 0.90649 * sin),
+                    f + amplitude * (1.97294 * cos),
+                );
+                // like the original, clip to the cube
+                Oklab::from_color_unclamped(rgb.clamp().into_linear())
+            }
+        }
+    }
+
+    fn render(&self, size: (usize, usize), region: Rect, buf: &mut [Srgb]) {
+        render_par(size, region, buf, |x, y| {
+            let lab = self.sample(x, y);
+            if self.extend {
+                oklab_to_srgb_clipped(lab)
+            } else {
+                oklab_to_srgb(&lab)
+            }
+        });
+        if self.smooth > 0. && self.extend {
+            smooth_par(size, region, buf, self.smooth);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::designer::Designer;
+
+    #[test]
+    fn classic_rgb_formulation() {
+        let mut gradient = Gradient::new();
+        gradient.formulation = Formulation::Rgb;
+        let black = gradient.sample(0., 0.5);
+        let white = gradient.sample(1., 0.5);
+        assert!(black.l.abs() < 1e-3 && (white.l - 1.).abs() < 1e-3);
+        // clipped to the cube, up to the precision of the round trip through Oklab
+        for i in 0..=100 {
+            let rgb = Srgb::from_color_unclamped(gradient.sample(i as f32 / 100., 0.5));
+            for c in [rgb.red, rgb.green, rgb.blue] {
+                assert!((-1e-3..=1. + 1e-3).contains(&c), "{rgb:?}");
+            }
+        }
+        // the middle of matplotlib's cubehelix
+        let mid = Srgb::from_color_unclamped(gradient.sample(0.5, 0.5));
+        let expected = [0.6275, 0.4750, 0.2864];
+        for (c, e) in [mid.red, mid.green, mid.blue].into_iter().zip(expected) {
+            assert!((c - e).abs() < 1e-3, "{mid:?}");
+        }
+    }
+}
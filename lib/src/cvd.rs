@@ -0,0 +1,86 @@
+use glam::{vec3, Mat3, Vec3};
+use palette::{LinSrgb, Srgb};
+use strum_macros::EnumIter;
+
+/// Types of color vision deficiency that can be simulated.
+#[derive(EnumIter, Debug, PartialEq, Default, Copy, Clone)]
+pub enum Deficiency {
+    #[default]
+    Protanopia,
+    Deuteranopia,
+    Tritanopia,
+}
+
+impl Deficiency {
+    /// Simulation matrices for linear rgb at full severity, from
+    /// "A Physiologically-based Model for Simulation of Color Vision Deficiency" by Machado et al.
+    fn matrix(self) -> Mat3 {
+        let rows = match self {
+            Deficiency::Protanopia => [
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ],
+            Deficiency::Deuteranopia => [
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ],
+            Deficiency::Tritanopia => [
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ],
+        };
+        Mat3::from_cols_array_2d(&rows).transpose()
+    }
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Simulation {
+    pub deficiency: Deficiency,
+    /// 0 is normal vision, 1 is full dichromacy
+    pub severity: f32,
+}
+
+impl Simulation {
+    pub const SEVERITY_DEFAULT: f32 = 1.;
+
+    pub fn new() -> Self {
+        Self {
+            deficiency: Deficiency::default(),
+            severity: Self::SEVERITY_DEFAULT,
+        }
+    }
+
+    /// Simulate how a color is perceived.
+    /// Partial severities are approximated by interpolating towards the full severity matrix.
+    pub fn apply(&self, rgb: Srgb) -> Srgb {
+        let linear = rgb.into_linear();
+        let m = Mat3::IDENTITY * (1. - self.severity) + self.deficiency.matrix() * self.severity;
+        let v = (m * vec3(linear.red, linear.green, linear.blue)).clamp(Vec3::ZERO, Vec3::ONE);
+        Srgb::from_linear(LinSrgb::new(v.x, v.y, v.z))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn neutrals_are_preserved() {
+        for deficiency in Deficiency::iter() {
+            let simulation = Simulation {
+                deficiency,
+                severity: 1.,
+            };
+            for v in [0., 0.5, 1.] {
+                let c = simulation.apply(Srgb::new(v, v, v));
+                for component in [c.red, c.green, c.blue] {
+                    assert!((component - v).abs() < 0.001, "{deficiency:?} {v}");
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,15 @@
-use palette::Srgb;
+use eframe::emath::Rect;
+use palette::{Oklab, Srgb};
 
 pub(crate) trait Designer {
     fn show_ui(&mut self, ui: &mut eframe::egui::Ui) -> bool;
-    fn render(&self, size: (usize, usize), buf: &mut [Srgb]);
+    /// The unclipped color at a point of the design, in the same coordinates as [`Designer::render`].
+    fn sample(&self, x: f32, y: f32) -> Oklab;
+    /// Render the part of the design covered by `region` into `buf`.
+    /// The design spans `0..1` on both axes, but `region` is allowed to extend outside of that.
+    fn render(&self, size: (usize, usize), region: Rect, buf: &mut [Srgb]);
+    /// The curve through Oklab that the design is built from, if it has one.
+    fn path(&self) -> Vec<Oklab> {
+        Vec::new()
+    }
 }
@@ -0,0 +1,160 @@
+use crate::{
+    designer, gamut_mapping,
+    utils::{
+        oklab_to_srgb, oklab_to_srgb_clipped, range_sliders, render_par, resettable_slider,
+        smooth_par,
+    },
+};
+use eframe::emath::Rect;
+use palette::{Oklab, Srgb};
+use std::f32::consts::TAU;
+
+/// Diverging colormap along x: two arms of different hue that meet at a neutral midpoint,
+/// with the same lightness and chroma at the same distance from the middle.
+#[derive(PartialEq, Clone)]
+pub struct Gradient {
+    /// hue of the left and right arm
+    hues: (f32, f32),
+    /// lightness of the neutral midpoint
+    midpoint: f32,
+    /// lightness at both ends
+    end_lightness: f32,
+    /// shape of the lightness profile, 1 is linear
+    lightness_power: f32,
+    /// chroma at the ends
+    chroma: f32,
+    /// cap the chroma of both arms to what the other arm can reach in gamut
+    balance: bool,
+    extend: bool,
+    smooth: f32,
+}
+
+impl Gradient {
+    const HUES_DEFAULT: (f32, f32) = (4.4, 0.6);
+    const MIDPOINT_DEFAULT: f32 = 0.95;
+    const END_LIGHTNESS_DEFAULT: f32 = 0.4;
+    const LIGHTNESS_POWER_DEFAULT: f32 = 1.;
+    const CHROMA_DEFAULT: f32 = 0.2;
+    const SMOOTH_DEFAULT: f32 = 0.;
+
+    pub fn new() -> Self {
+        Self {
+            hues: Self::HUES_DEFAULT,
+            midpoint: Self::MIDPOINT_DEFAULT,
+            end_lightness: Self::END_LIGHTNESS_DEFAULT,
+            lightness_power: Self::LIGHTNESS_POWER_DEFAULT,
+            chroma: Self::CHROMA_DEFAULT,
+            balance: true,
+            extend: true,
+            smooth: Self::SMOOTH_DEFAULT,
+        }
+    }
+
+    /// Lightness and chroma at distance `t` from the middle, shared by both arms.
+    fn profile(&self, t: f32) -> (f32, f32) {
+        let l = self.midpoint + (self.end_lightness - self.midpoint) * t.powf(self.lightness_power);
+        let mut c = self.chroma * t;
+        if self.balance {
+            let [(a0, b0), (a1, b1)] = [self.hues.0, self.hues.1].map(|h| {
+                let (b, a) = h.sin_cos();
+                (a, b)
+            });
+            c = c
+                .min(gamut_mapping::max_chroma(l, a0, b0))
+                .min(gamut_mapping::max_chroma(l, a1, b1));
+        }
+        (l, c)
+    }
+}
+
+impl designer::Designer for Gradient {
+    fn show_ui(&mut self, ui: &mut eframe::egui::Ui) -> bool {
+        let mut c = self.clone();
+        let Gradient {
+            hues,
+            midpoint,
+            end_lightness,
+            lightness_power,
+            chroma,
+            balance,
+            extend,
+            smooth,
+        } = &mut c;
+        ui.vertical(|ui| {
+            range_sliders(
+                ui,
+                hues,
+                ["hue left", "hue right"],
+                0. ..=TAU,
+                Self::HUES_DEFAULT,
+            );
+            resettable_slider(ui, midpoint, "L middle", 0. ..=1., Self::MIDPOINT_DEFAULT);
+            resettable_slider(
+                ui,
+                end_lightness,
+                "L ends",
+                0. ..=1.,
+                Self::END_LIGHTNESS_DEFAULT,
+            );
+            resettable_slider(
+                ui,
+                lightness_power,
+                "L curve",
+                0.2..=5.,
+                Self::LIGHTNESS_POWER_DEFAULT,
+            );
+            resettable_slider(ui, chroma, "chroma", 0. ..=0.4, Self::CHROMA_DEFAULT);
+            ui.checkbox(balance, "balance chroma")
+                .on_hover_text("limit both arms to the chroma that both can reach in gamut");
+            ui.checkbox(extend, "extend");
+            resettable_slider(ui, smooth, "smooth", 0. ..=100., Self::SMOOTH_DEFAULT);
+        });
+        if c != *self {
+            *self = c;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn sample(&self, x: f32, _y: f32) -> Oklab {
+        let t = (2. * x - 1.).clamp(-1., 1.);
+        let hue = if t < 0. { self.hues.0 } else { self.hues.1 };
+        let t = t.abs();
+        let (l, c) = self.profile(t);
+        let (b, a) = hue.sin_cos();
+        Oklab::new(l, c * a, c * b)
+    }
+
+    fn render(&self, size: (usize, usize), region: Rect, buf: &mut [Srgb]) {
+        render_par(size, region, buf, |x, y| {
+            let lab = self.sample(x, y);
+            if self.extend {
+                oklab_to_srgb_clipped(lab)
+            } else {
+                oklab_to_srgb(&lab)
+            }
+        });
+        if self.smooth > 0. && self.extend {
+            smooth_par(size, region, buf, self.smooth);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{designer::Designer, utils::is_in_gamut};
+
+    #[test]
+    fn arms_are_balanced() {
+        let gradient = Gradient::new();
+        for i in 0..=50 {
+            let x = i as f32 / 100.;
+            let (left, right) = (gradient.sample(x, 0.5), gradient.sample(1. - x, 0.5));
+            assert!((left.l - right.l).abs() < 1e-5);
+            assert!((left.a.hypot(left.b) - right.a.hypot(right.b)).abs() < 1e-5);
+            assert!(is_in_gamut(left) && is_in_gamut(right));
+        }
+    }
+}
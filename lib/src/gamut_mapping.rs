@@ -158,12 +158,12 @@ fn compute_max_saturation(a: f32, b: f32) -> f32 {
 
 // finds L_cusp and C_cusp for a given hue
 // a and b must be normalized so a^2 + b^2 == 1
-struct LC {
-    l: f32,
-    c: f32,
+pub struct LC {
+    pub l: f32,
+    pub c: f32,
 }
 
-fn find_cusp(a: f32, b: f32) -> LC {
+pub fn find_cusp(a: f32, b: f32) -> LC {
     debug_assert!((a.powi(2) + b.powi(2) - 1.).abs() < 0.0001);
     // First, find the maximum saturation (saturation S = C/L)
     let s_cusp = compute_max_saturation(a, b);
@@ -187,7 +187,7 @@ fn find_cusp(a: f32, b: f32) -> LC {
 // L = L0 * (1 - t) + t * L1;
 // C = t * C1;
 // a and b must be normalized so a^2 + b^2 == 1
-fn find_gamut_intersection(a: f32, b: f32, l1: f32, c1: f32, l0: f32) -> f32 {
+pub fn find_gamut_intersection(a: f32, b: f32, l1: f32, c1: f32, l0: f32) -> f32 {
     debug_assert!((a.powi(2) + b.powi(2) - 1.).abs() < 0.0001);
     // Find the cusp of the gamut triangle
     let cusp = find_cusp(a, b);
@@ -269,6 +269,16 @@ fn find_gamut_intersection(a: f32, b: f32, l1: f32, c1: f32, l0: f32) -> f32 {
     }
 }
 
+// Finds the maximum chroma that fits in sRGB for a given lightness and hue
+// a and b must be normalized so a^2 + b^2 == 1
+pub fn max_chroma(l: f32, a: f32, b: f32) -> f32 {
+    if l <= 0. || l >= 1. {
+        return 0.;
+    }
+    // with C1 == 1 along a line of constant L, t is the chroma
+    find_gamut_intersection(a, b, l, 1., l).max(0.)
+}
+
 /// like f32::signum except for no Nan and returns 0 if x is 0
 fn sgn(x: f32) -> f32 {
     ((0. < x) as i8 - (x < 0.) as i8) as f32
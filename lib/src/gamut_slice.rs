@@ -0,0 +1,273 @@
+use std::f32::consts::TAU;
+
+use eframe::{
+    egui::{self, Sense},
+    emath::{pos2, vec2, Pos2},
+    epaint::{Color32, Shape, Stroke},
+};
+use palette::{convert::FromColorUnclamped, Oklab, Oklch};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use crate::{
+    designer::Designer,
+    gamut_mapping,
+    utils::{is_in_gamut, oklab_to_srgb_clipped, resettable_slider, srgb_to_color32, UNIT_RECT},
+};
+
+#[derive(EnumIter, Debug, PartialEq, Default, Copy, Clone)]
+enum Slice {
+    #[default]
+    AB,
+    LC,
+}
+
+impl Slice {
+    fn label(self) -> &'static str {
+        match self {
+            Slice::AB => "a/b at fixed L",
+            Slice::LC => "L/C at fixed hue",
+        }
+    }
+}
+
+/// Slice through Oklab showing the sRGB gamut, with the colors of the design plotted on top.
+pub struct GamutSlice {
+    pub open: bool,
+    slice: Slice,
+    lightness: f32,
+    /// in degrees
+    hue: f32,
+    /// only plot the points of the design that are close to the slice
+    near_only: bool,
+    /// the shaded gamut, along with the slice and lightness or hue it was made for
+    background: Option<(Slice, f32, egui::TextureHandle)>,
+    points: Option<Vec<Oklab>>,
+}
+
+impl GamutSlice {
+    const LIGHTNESS_DEFAULT: f32 = 0.7;
+    const HUE_DEFAULT: f32 = 30.;
+    /// extent of the chroma axes
+    const MAX_CHROMA: f32 = 0.4;
+    const BACKGROUND_SIZE: usize = 128;
+    const POINTS_GRID: usize = 32;
+    const NEAR_LIGHTNESS: f32 = 0.05;
+    const NEAR_HUE: f32 = 10.;
+
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            slice: Slice::default(),
+            lightness: Self::LIGHTNESS_DEFAULT,
+            hue: Self::HUE_DEFAULT,
+            near_only: false,
+            background: None,
+            points: None,
+        }
+    }
+
+    pub fn invalidate(&mut self) {
+        self.points = None;
+    }
+
+    /// The normalized hue direction of the L/C slice.
+    fn hue_ab(&self) -> (f32, f32) {
+        let (b, a) = self.hue.to_radians().sin_cos();
+        (a, b)
+    }
+
+    /// The lightness or hue that selects the current slice.
+    fn slice_param(&self) -> f32 {
+        match self.slice {
+            Slice::AB => self.lightness,
+            Slice::LC => self.hue,
+        }
+    }
+
+    /// Color at a position in the plot, where both axes go from 0 to 1 and y points down.
+    fn plot_to_lab(&self, p: Pos2) -> Oklab {
+        match self.slice {
+            Slice::AB => Oklab::new(
+                self.lightness,
+                (2. * p.x - 1.) * Self::MAX_CHROMA,
+                (1. - 2. * p.y) * Self::MAX_CHROMA,
+            ),
+            Slice::LC => {
+                let (a, b) = self.hue_ab();
+                let chroma = p.x * Self::MAX_CHROMA;
+                Oklab::new(1. - p.y, chroma * a, chroma * b)
+            }
+        }
+    }
+
+    fn lab_to_plot(&self, lab: Oklab) -> Pos2 {
+        match self.slice {
+            Slice::AB => pos2(
+                (lab.a / Self::MAX_CHROMA + 1.) / 2.,
+                (1. - lab.b / Self::MAX_CHROMA) / 2.,
+            ),
+            Slice::LC => pos2(
+                Oklch::from_color_unclamped(lab).chroma / Self::MAX_CHROMA,
+                1. - lab.l,
+            ),
+        }
+    }
+
+    fn is_near(&self, lab: Oklab) -> bool {
+        match self.slice {
+            Slice::AB => (lab.l - self.lightness).abs() < Self::NEAR_LIGHTNESS,
+            Slice::LC => {
+                let hue = Oklch::from_color_unclamped(lab).hue.to_positive_degrees();
+                let d = (hue - self.hue).rem_euclid(360.);
+                d.min(360. - d) < Self::NEAR_HUE
+            }
+        }
+    }
+
+    /// The edge of the gamut within the slice.
+    fn boundary(&self) -> Vec<Oklab> {
+        const STEPS: usize = 180;
+        match self.slice {
+            Slice::AB => (0..=STEPS)
+                .map(|i| {
+                    let (b, a) = (i as f32 / STEPS as f32 * TAU).sin_cos();
+                    let c = gamut_mapping::max_chroma(self.lightness, a, b);
+                    Oklab::new(self.lightness, c * a, c * b)
+                })
+                .collect(),
+            Slice::LC => {
+                let (a, b) = self.hue_ab();
+                (0..=STEPS)
+                    .map(|i| {
+                        let l = i as f32 / STEPS as f32;
+                        let c = gamut_mapping::max_chroma(l, a, b);
+                        Oklab::new(l, c * a, c * b)
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    fn make_background(&self) -> egui::ColorImage {
+        const N: usize = GamutSlice::BACKGROUND_SIZE;
+        let pixels = (0..N)
+            .flat_map(|y| {
+                (0..N).map(move |x| {
+                    let lab = self.plot_to_lab(pos2(
+                        (x as f32 + 0.5) / N as f32,
+                        (y as f32 + 0.5) / N as f32,
+                    ));
+                    if is_in_gamut(lab) {
+                        srgb_to_color32(oklab_to_srgb_clipped(lab))
+                    } else {
+                        Color32::TRANSPARENT
+                    }
+                })
+            })
+            .collect();
+        egui::ColorImage {
+            size: [N, N],
+            pixels,
+        }
+    }
+
+    pub fn show_window(&mut self, ctx: &egui::Context, designer: &dyn Designer) {
+        let mut open = self.open;
+        egui::Window::new("gamut slice")
+            .open(&mut open)
+            .default_width(300.)
+            .show(ctx, |ui| self.ui(ui, designer));
+        self.open = open;
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, designer: &dyn Designer) {
+        ui.horizontal(|ui| {
+            for slice in Slice::iter() {
+                ui.selectable_value(&mut self.slice, slice, slice.label());
+            }
+        });
+        match self.slice {
+            Slice::AB => resettable_slider(
+                ui,
+                &mut self.lightness,
+                "L",
+                0. ..=1.,
+                Self::LIGHTNESS_DEFAULT,
+            ),
+            Slice::LC => resettable_slider(ui, &mut self.hue, "hue", 0. ..=360., Self::HUE_DEFAULT),
+        }
+        ui.checkbox(&mut self.near_only, "only points near the slice");
+        if !matches!(&self.background, Some((slice, param, _)) if *slice == self.slice && *param == self.slice_param())
+        {
+            let texture = ui.ctx().load_texture(
+                "gamut slice",
+                self.make_background(),
+                egui::TextureOptions::default(),
+            );
+            self.background = Some((self.slice, self.slice_param(), texture));
+        }
+        if self.points.is_none() {
+            let n = Self::POINTS_GRID;
+            self.points = Some(
+                (0..n * n)
+                    .map(|i| {
+                        designer.sample(
+                            (i % n) as f32 / (n - 1) as f32,
+                            (i / n) as f32 / (n - 1) as f32,
+                        )
+                    })
+                    .collect(),
+            );
+        }
+        let points = self.points.as_ref().unwrap();
+
+        let side = ui.available_width().min(400.);
+        let (response, painter) = ui.allocate_painter(vec2(side, side), Sense::hover());
+        let rect = response.rect;
+        let to_screen = |p: Pos2| rect.min + p.to_vec2() * rect.size();
+        let visuals = ui.visuals();
+        painter.rect_filled(rect, 0., visuals.extreme_bg_color);
+        if let Some((_, _, texture)) = &self.background {
+            painter.image(texture.id(), rect, UNIT_RECT, Color32::WHITE);
+        }
+        let axis_stroke = visuals.widgets.noninteractive.bg_stroke;
+        if self.slice == Slice::AB {
+            painter.hline(rect.x_range(), rect.center().y, axis_stroke);
+            painter.vline(rect.center().x, rect.y_range(), axis_stroke);
+        }
+        let fg_stroke = visuals.widgets.noninteractive.fg_stroke;
+        let boundary = self
+            .boundary()
+            .into_iter()
+            .map(|lab| to_screen(self.lab_to_plot(lab)))
+            .collect();
+        painter.add(Shape::line(boundary, fg_stroke));
+        if self.slice == Slice::LC {
+            let (a, b) = self.hue_ab();
+            let cusp = gamut_mapping::find_cusp(a, b);
+            painter.circle_stroke(
+                to_screen(self.lab_to_plot(Oklab::new(cusp.l, cusp.c * a, cusp.c * b))),
+                3.,
+                fg_stroke,
+            );
+        }
+        for &lab in points {
+            if self.near_only && !self.is_near(lab) {
+                continue;
+            }
+            let outline = if is_in_gamut(lab) {
+                Color32::WHITE
+            } else {
+                Color32::RED
+            };
+            painter.circle(
+                to_screen(self.lab_to_plot(lab)),
+                2.5,
+                srgb_to_color32(oklab_to_srgb_clipped(lab)),
+                Stroke::new(1., outline),
+            );
+        }
+        ui.weak("points outside of the gamut have a red outline");
+    }
+}
@@ -1,12 +1,14 @@
 use crate::{
-    blur, designer,
-    utils::{oklab_to_srgb, oklab_to_srgb_clipped, render_par, resettable_slider, NEUTRAL_LAB},
+    designer,
+    utils::{
+        color_slider, oklab_to_srgb, oklab_to_srgb_clipped, render_par, resettable_slider,
+        smooth_par, NEUTRAL_LAB,
+    },
+    working_space::WorkingSpace,
 };
+use eframe::emath::Rect;
 use glam::{vec2, Vec2};
-use palette::{convert::FromColorUnclamped, Oklab, Srgb};
-use rayon::iter::{
-    IndexedParallelIterator, IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator,
-};
+use palette::{Oklab, Srgb};
 use std::f32::consts::PI;
 
 #[derive(PartialEq, Clone)]
@@ -18,6 +20,7 @@ pub struct Gradient {
     saturation_non_midtone: f32,
     twist: f32,
     twist_v: f32,
+    space: WorkingSpace,
     extend: bool,
     smooth: f32,
 }
@@ -40,6 +43,7 @@ impl Gradient {
             saturation_non_midtone: Self::SATURATION_NON_MIDTONE_DEFAULT,
             twist: Self::TWIST_DEFAULT,
             twist_v: Self::TWIST_V_DEFAULT,
+            space: WorkingSpace::new(),
             extend: true,
             smooth: Self::SMOOTH_DEFAULT,
         }
@@ -57,30 +61,38 @@ impl designer::Designer for Gradient {
             saturation_non_midtone,
             twist,
             twist_v,
+            space,
             extend,
             smooth,
         } = &mut c;
         ui.vertical(|ui| {
-            resettable_slider(
+            space.show_ui(ui);
+            let lab = *center;
+            color_slider(
                 ui,
                 &mut center.l,
                 "L center",
                 Oklab::min_l()..=Oklab::max_l(),
                 Self::CENTER_DEFAULT.l,
+                |l| space.to_oklab(Oklab { l, ..lab }),
             );
-            resettable_slider(
+            let lab = *center;
+            color_slider(
                 ui,
                 &mut center.a,
                 "a center",
                 Oklab::<f32>::min_a() * 2.0..=Oklab::<f32>::max_a() * 2.,
                 Self::CENTER_DEFAULT.a,
+                |a| space.to_oklab(Oklab { a, ..lab }),
             );
-            resettable_slider(
+            let lab = *center;
+            color_slider(
                 ui,
                 &mut center.b,
                 "b center",
                 Oklab::<f32>::min_b() * 2.0..=Oklab::<f32>::max_b() * 2.,
                 Self::CENTER_DEFAULT.b,
+                |b| space.to_oklab(Oklab { b, ..lab }),
             );
             resettable_slider(
                 ui,
@@ -123,20 +135,23 @@ impl designer::Designer for Gradient {
         }
     }
 
-    fn render(&self, size: (usize, usize), buf: &mut [Srgb]) {
-        render_par(size, buf, |x, y| {
-            let xcenter = 2. * (x - 0.5);
-            let ycenter = 2. * (y - 0.5);
-            let lightness = self.center.l - ycenter * 0.5;
-            let twist = self.twist + y * self.twist_v;
-            let rot =
-                Vec2::from_angle(xcenter * 0.5 * self.rotation + self.phase + ycenter * twist);
-            let midtone_mask = ((lightness - NEUTRAL_LAB.l).abs() * 2.).powi(2);
-            let saturation = (self.saturation
-                * (1. - (1. - self.saturation_non_midtone) * midtone_mask))
-                .max(0.);
-            let chroma = vec2(rot.x, rot.y) * saturation + vec2(self.center.a, self.center.b);
-            let lab = Oklab::new(lightness, chroma.x, chroma.y);
+    fn sample(&self, x: f32, y: f32) -> Oklab {
+        let xcenter = 2. * (x - 0.5);
+        let ycenter = 2. * (y - 0.5);
+        let lightness = self.center.l - ycenter * 0.5;
+        let twist = self.twist + y * self.twist_v;
+        let rot = Vec2::from_angle(xcenter * 0.5 * self.rotation + self.phase + ycenter * twist);
+        let midtone_mask = ((lightness - NEUTRAL_LAB.l).abs() * 2.).powi(2);
+        let saturation =
+            (self.saturation * (1. - (1. - self.saturation_non_midtone) * midtone_mask)).max(0.);
+        let chroma = vec2(rot.x, rot.y) * saturation + vec2(self.center.a, self.center.b);
+        self.space
+            .to_oklab(Oklab::new(lightness, chroma.x, chroma.y))
+    }
+
+    fn render(&self, size: (usize, usize), region: Rect, buf: &mut [Srgb]) {
+        render_par(size, region, buf, |x, y| {
+            let lab = self.sample(x, y);
             if self.extend {
                 oklab_to_srgb_clipped(lab)
             } else {
@@ -144,17 +159,7 @@ impl designer::Designer for Gradient {
             }
         });
         if self.smooth > 0. {
-            // TODO have rayon split the work into bigger chunks to reduce sync?
-            let mut labbuf: Vec<_> = buf
-                .par_iter()
-                .map(|c| palette::Oklab::from_color_unclamped(c.into_linear()))
-                .collect();
-            blur::gaussian_blur(labbuf.as_mut(), size.0, size.1, self.smooth);
-            labbuf
-                .par_iter()
-                .copied()
-                .zip(buf.par_iter_mut())
-                .for_each(|(a, b)| *b = oklab_to_srgb_clipped(a));
+            smooth_par(size, region, buf, self.smooth);
         }
     }
 }
@@ -0,0 +1,368 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{
+    designer,
+    utils::{
+        delta_e_ok, oklab_to_srgb, oklab_to_srgb_clipped, oklab_to_vec3, render_par,
+        resettable_slider, show_error, vec3_to_oklab, NEUTRAL_LAB,
+    },
+};
+use eframe::emath::Rect;
+use glam::Vec3;
+use native_dialog::FileDialog;
+use palette::{convert::FromColorUnclamped, Oklab, Srgb};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+#[derive(EnumIter, Debug, PartialEq, Default, Copy, Clone)]
+enum Clustering {
+    #[default]
+    KMeans,
+    MedianCut,
+}
+
+#[derive(EnumIter, Debug, PartialEq, Default, Copy, Clone)]
+enum Order {
+    #[default]
+    Lightness,
+    /// start at the darkest color and always go to the closest remaining one
+    NearestNeighbour,
+}
+
+#[derive(EnumIter, Debug, PartialEq, Default, Copy, Clone)]
+enum Style {
+    #[default]
+    Smooth,
+    Swatches,
+}
+
+/// Pixels of the loaded image.
+/// Compared by identity, since comparing the contents every frame would be slow.
+#[derive(Clone)]
+struct Pixels(Arc<Vec<Oklab>>);
+
+impl PartialEq for Pixels {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// Dominant colors of an image, ordered along a path and spread over the x axis.
+#[derive(PartialEq, Clone)]
+pub struct Gradient {
+    path: Option<PathBuf>,
+    pixels: Option<Pixels>,
+    count: usize,
+    clustering: Clustering,
+    order: Order,
+    style: Style,
+    /// the extracted palette
+    colors: Vec<Oklab>,
+    extend: bool,
+}
+
+impl Gradient {
+    const COUNT_DEFAULT: usize = 6;
+    /// images are subsampled to at most this many pixels before clustering
+    const MAX_PIXELS: usize = 1 << 14;
+    const KMEANS_ITERATIONS: usize = 20;
+
+    pub fn new() -> Self {
+        Self {
+            path: None,
+            pixels: None,
+            count: Self::COUNT_DEFAULT,
+            clustering: Clustering::default(),
+            order: Order::default(),
+            style: Style::default(),
+            // something to show before an image is loaded
+            colors: vec![
+                Oklab::new(0.3, 0.02, -0.1),
+                Oklab::new(0.5, 0.1, -0.05),
+                Oklab::new(0.65, 0.12, 0.06),
+                Oklab::new(0.8, 0.03, 0.13),
+                Oklab::new(0.92, -0.02, 0.05),
+            ],
+            extend: true,
+        }
+    }
+
+    fn load(path: &Path) -> Result<Pixels, image::ImageError> {
+        let image = image::open(path)?.to_rgb8();
+        let stride = (image.pixels().len() / Self::MAX_PIXELS).max(1);
+        Ok(Pixels(Arc::new(
+            image
+                .pixels()
+                .step_by(stride)
+                .map(|p| {
+                    let rgb: Srgb = Srgb::new(p[0], p[1], p[2]).into_format();
+                    Oklab::from_color_unclamped(rgb.into_linear())
+                })
+                .collect(),
+        )))
+    }
+
+    fn extract(&mut self) {
+        let Some(Pixels(pixels)) = &self.pixels else {
+            return;
+        };
+        let pixels: Vec<Vec3> = pixels.iter().map(|&p| oklab_to_vec3(p)).collect();
+        if pixels.is_empty() {
+            return;
+        }
+        let colors = match self.clustering {
+            Clustering::KMeans => kmeans(&pixels, self.count, Self::KMEANS_ITERATIONS),
+            Clustering::MedianCut => median_cut(&pixels, self.count),
+        };
+        // keep the previous palette rather than ending up with none
+        if colors.is_empty() {
+            return;
+        }
+        self.colors = order(colors.into_iter().map(vec3_to_oklab).collect(), self.order);
+    }
+}
+
+/// Farthest point initialization followed by Lloyd iterations.
+/// Deterministic, so the same image always gives the same palette.
+fn kmeans(pixels: &[Vec3], k: usize, iterations: usize) -> Vec<Vec3> {
+    if pixels.is_empty() {
+        return Vec::new();
+    }
+    let mean = pixels.iter().sum::<Vec3>() / pixels.len() as f32;
+    let nearest = |centroids: &[Vec3], p: Vec3| {
+        centroids
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (i, c.distance_squared(p)))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .unwrap()
+    };
+    let mut centroids = vec![*pixels
+        .iter()
+        .min_by(|a, b| {
+            a.distance_squared(mean)
+                .total_cmp(&b.distance_squared(mean))
+        })
+        .unwrap()];
+    while centroids.len() < k {
+        let farthest = *pixels
+            .iter()
+            .max_by(|&&a, &&b| {
+                nearest(&centroids, a)
+                    .1
+                    .total_cmp(&nearest(&centroids, b).1)
+            })
+            .unwrap();
+        centroids.push(farthest);
+    }
+    for _ in 0..iterations {
+        let mut sums = vec![(Vec3::ZERO, 0); k];
+        for &p in pixels {
+            let (i, _) = nearest(&centroids, p);
+            sums[i].0 += p;
+            sums[i].1 += 1;
+        }
+        // empty clusters keep their old centroid
+        for (centroid, (sum, n)) in centroids.iter_mut().zip(sums) {
+            if n > 0 {
+                *centroid = sum / n as f32;
+            }
+        }
+    }
+    centroids
+}
+
+/// Repeatedly split the box with the largest extent at the median of its widest axis.
+fn median_cut(pixels: &[Vec3], k: usize) -> Vec<Vec3> {
+    let extent = |b: &[Vec3]| {
+        let (min, max) = b
+            .iter()
+            .fold((Vec3::INFINITY, Vec3::NEG_INFINITY), |(min, max), &p| {
+                (min.min(p), max.max(p))
+            });
+        max - min
+    };
+    let mut boxes = vec![pixels.to_vec()];
+    while boxes.len() < k {
+        let Some((i, size)) = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.len() > 1)
+            .map(|(i, b)| (i, extent(b)))
+            .max_by(|(_, a), (_, b)| a.max_element().total_cmp(&b.max_element()))
+        else {
+            break;
+        };
+        let axis = if size.x >= size.y && size.x >= size.z {
+            0
+        } else if size.y >= size.z {
+            1
+        } else {
+            2
+        };
+        let mut b = boxes.swap_remove(i);
+        b.sort_by(|p, q| p[axis].total_cmp(&q[axis]));
+        let upper = b.split_off(b.len() / 2);
+        boxes.push(b);
+        boxes.push(upper);
+    }
+    boxes
+        .iter()
+        .filter(|b| !b.is_empty())
+        .map(|b| b.iter().sum::<Vec3>() / b.len() as f32)
+        .collect()
+}
+
+fn order(mut colors: Vec<Oklab>, order: Order) -> Vec<Oklab> {
+    colors.sort_by(|a, b| a.l.total_cmp(&b.l));
+    match order {
+        Order::Lightness => colors,
+        Order::NearestNeighbour => {
+            let mut remaining = colors;
+            let mut path = Vec::with_capacity(remaining.len());
+            if !remaining.is_empty() {
+                path.push(remaining.remove(0));
+            }
+            while !remaining.is_empty() {
+                let last = *path.last().unwrap();
+                let (i, _) = remaining
+                    .iter()
+                    .enumerate()
+                    .min_by(|(_, a), (_, b)| {
+                        delta_e_ok(last, **a).total_cmp(&delta_e_ok(last, **b))
+                    })
+                    .unwrap();
+                path.push(remaining.remove(i));
+            }
+            path
+        }
+    }
+}
+
+impl designer::Designer for Gradient {
+    fn show_ui(&mut self, ui: &mut eframe::egui::Ui) -> bool {
+        let mut c = self.clone();
+        let params = (c.count, c.clustering, c.order);
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                if ui.button("📂 load image").clicked() {
+                    if let Ok(Some(path)) = FileDialog::new()
+                        .add_filter("PNG Image", &["png"])
+                        .show_open_single_file()
+                    {
+                        match Self::load(&path) {
+                            Ok(pixels) => {
+                                c.pixels = Some(pixels);
+                                c.path = Some(path);
+                            }
+                            Err(e) => show_error("Error loading image", &e),
+                        }
+                    }
+                }
+                if let Some(name) = c.path.as_ref().and_then(|p| p.file_name()) {
+                    ui.label(name.to_string_lossy());
+                }
+            });
+            let Gradient {
+                count,
+                clustering,
+                order,
+                style,
+                extend,
+                ..
+            } = &mut c;
+            resettable_slider(ui, count, "colors", 2..=16, Self::COUNT_DEFAULT);
+            ui.horizontal(|ui| {
+                for m in Clustering::iter() {
+                    ui.selectable_value(clustering, m, format!("{:?}", m));
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("order");
+                for o in Order::iter() {
+                    ui.selectable_value(order, o, format!("{:?}", o));
+                }
+            });
+            ui.horizontal(|ui| {
+                for s in Style::iter() {
+                    ui.selectable_value(style, s, format!("{:?}", s));
+                }
+            });
+            ui.checkbox(extend, "extend");
+        });
+        if c.pixels != self.pixels || params != (c.count, c.clustering, c.order) {
+            c.extract();
+        }
+        if c != *self {
+            *self = c;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn sample(&self, x: f32, _y: f32) -> Oklab {
+        let n = self.colors.len();
+        let t = x.clamp(0., 1.);
+        match self.style {
+            _ if n == 0 => NEUTRAL_LAB,
+            Style::Swatches => self.colors[((t * n as f32) as usize).min(n - 1)],
+            Style::Smooth if n == 1 => self.colors[0],
+            Style::Smooth => {
+                let f = t * (n - 1) as f32;
+                let i = (f as usize).min(n - 2);
+                vec3_to_oklab(
+                    oklab_to_vec3(self.colors[i])
+                        .lerp(oklab_to_vec3(self.colors[i + 1]), f - i as f32),
+                )
+            }
+        }
+    }
+
+    fn render(&self, size: (usize, usize), region: Rect, buf: &mut [Srgb]) {
+        render_par(size, region, buf, |x, y| {
+            let lab = self.sample(x, y);
+            if self.extend {
+                oklab_to_srgb_clipped(lab)
+            } else {
+                oklab_to_srgb(&lab)
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::vec3;
+
+    #[test]
+    fn clusters_are_found() {
+        let dark = vec3(0.2, 0.05, 0.);
+        let light = vec3(0.9, -0.05, 0.1);
+        let pixels: Vec<Vec3> = (0..100)
+            .map(|i| {
+                let jitter = Vec3::splat((i % 5) as f32 * 0.002);
+                if i % 2 == 0 {
+                    dark + jitter
+                } else {
+                    light + jitter
+                }
+            })
+            .collect();
+        for colors in [kmeans(&pixels, 2, 10), median_cut(&pixels, 2)] {
+            let mut colors = order(
+                colors.into_iter().map(vec3_to_oklab).collect(),
+                Order::Lightness,
+            );
+            assert_eq!(colors.len(), 2);
+            let light_found = oklab_to_vec3(colors.pop().unwrap());
+            let dark_found = oklab_to_vec3(colors.pop().unwrap());
+            assert!(dark_found.distance(dark) < 0.01);
+            assert!(light_found.distance(light) < 0.01);
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use eframe::{
+    egui::{self, Sense},
+    emath::vec2,
+};
+use palette::{convert::FromColorUnclamped, Oklch, Srgb};
+
+use crate::{
+    canvas::Canvas,
+    designer::Designer,
+    utils::{srgb_to_color32, srgb_to_hex},
+};
+
+/// Show the color under the pointer in a tooltip, and optionally copy its hex code when clicked.
+pub fn show(
+    canvas: &Canvas,
+    response: &egui::Response,
+    designer: &dyn Designer,
+    click_to_copy: bool,
+) {
+    const SWATCH_SIZE: f32 = 32.;
+    if response.dragged() {
+        return;
+    }
+    let Some(pos) = canvas.hover_pos(response) else {
+        return;
+    };
+    let Some(rgb) = canvas.rendered_color(pos) else {
+        return;
+    };
+    let lab = designer.sample(pos.x, pos.y);
+    let lch = Oklch::from_color_unclamped(lab);
+    // covers clipping, projection and the black of designers that do not extend the gamut
+    let unclipped = Srgb::from_color_unclamped(lab);
+    let gamut_mapped = [
+        (rgb.red, unclipped.red),
+        (rgb.green, unclipped.green),
+        (rgb.blue, unclipped.blue),
+    ]
+    .iter()
+    .any(|(shown, sampled)| (shown - sampled).abs() > 1. / 255.);
+    let hex = srgb_to_hex(rgb);
+    response.ctx.set_cursor_icon(egui::CursorIcon::Crosshair);
+    if click_to_copy && response.clicked() {
+        response.ctx.copy_text(hex.clone());
+    }
+    response.clone().on_hover_ui_at_pointer(|ui| {
+        ui.horizontal(|ui| {
+            let (rect, _) = ui.allocate_exact_size(vec2(SWATCH_SIZE, SWATCH_SIZE), Sense::hover());
+            ui.painter().rect_filled(rect, 0., srgb_to_color32(rgb));
+            egui::Grid::new("inspector").show(ui, |ui| {
+                ui.label("x, y");
+                ui.monospace(format!("{:.3} {:.3}", pos.x, pos.y));
+                ui.end_row();
+                ui.label("Oklab");
+                ui.monospace(format!("{:.3} {:.3} {:.3}", lab.l, lab.a, lab.b));
+                ui.end_row();
+                ui.label("Oklch");
+                ui.monospace(format!(
+                    "{:.3} {:.3} {:.1}°",
+                    lch.l,
+                    lch.chroma,
+                    lch.hue.to_positive_degrees()
+                ));
+                ui.end_row();
+                ui.label("sRGB");
+                ui.monospace(&hex);
+                ui.end_row();
+            });
+        });
+        if gamut_mapped {
+            ui.label("changed by gamut mapping");
+        }
+        if click_to_copy {
+            ui.weak("click to copy");
+        }
+    });
+}
@@ -0,0 +1,110 @@
+use std::f32::consts::{PI, TAU};
+
+use glam::Vec3;
+use palette::Oklab;
+use strum_macros::EnumIter;
+
+use crate::utils::{oklab_to_vec3, vec3_to_oklab};
+
+/// Space that colors are interpolated in.
+#[derive(EnumIter, Debug, PartialEq, Default, Copy, Clone)]
+pub enum Space {
+    #[default]
+    Oklab,
+    Oklch,
+}
+
+/// Which way around the hue circle Oklch interpolation goes, like in css.
+#[derive(EnumIter, Debug, PartialEq, Default, Copy, Clone)]
+pub enum HueMode {
+    #[default]
+    Shorter,
+    Longer,
+    Increasing,
+    Decreasing,
+}
+
+#[derive(EnumIter, Debug, PartialEq, Default, Copy, Clone)]
+pub enum Easing {
+    #[default]
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    pub fn apply(self, t: f32) -> f32 {
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => 1. - (1. - t) * (1. - t),
+            Easing::EaseInOut => t * t * (3. - 2. * t),
+        }
+    }
+}
+
+/// Catmull-rom spline through `p[1]` and `p[2]`.
+pub fn catmull_rom(p: [Vec3; 4], t: f32) -> Vec3 {
+    0.5 * (2. * p[1]
+        + (p[2] - p[0]) * t
+        + (2. * p[0] - 5. * p[1] + 4. * p[2] - p[3]) * t * t
+        + (3. * p[1] - p[0] - 3. * p[2] + p[3]) * t * t * t)
+}
+
+/// [`catmull_rom`] for a single value.
+pub fn catmull_rom_scalar(p: [f32; 4], t: f32) -> f32 {
+    0.5 * (2. * p[1]
+        + (p[2] - p[0]) * t
+        + (2. * p[0] - 5. * p[1] + 4. * p[2] - p[3]) * t * t
+        + (3. * p[1] - p[0] - 3. * p[2] + p[3]) * t * t * t)
+}
+
+/// Hue difference to go from `h0` to `h1`, in radians.
+fn hue_delta(h0: f32, h1: f32, mode: HueMode) -> f32 {
+    let shorter = (h1 - h0 + PI).rem_euclid(TAU) - PI;
+    match mode {
+        HueMode::Shorter => shorter,
+        HueMode::Longer if shorter > 0. => shorter - TAU,
+        HueMode::Longer => shorter + TAU,
+        HueMode::Increasing => (h1 - h0).rem_euclid(TAU),
+        HueMode::Decreasing => -(h0 - h1).rem_euclid(TAU),
+    }
+}
+
+/// Interpolate from `a` to `b`, with `t` in `0..=1`.
+pub fn interpolate(a: Oklab, b: Oklab, t: f32, space: Space, hue_mode: HueMode) -> Oklab {
+    match space {
+        Space::Oklab => vec3_to_oklab(oklab_to_vec3(a).lerp(oklab_to_vec3(b), t)),
+        Space::Oklch => {
+            // below this the hue is meaningless, so the hue of the other color is used
+            const ACHROMATIC: f32 = 1e-4;
+            let (ca, cb) = (a.a.hypot(a.b), b.a.hypot(b.b));
+            let (mut ha, mut hb) = (a.b.atan2(a.a), b.b.atan2(b.a));
+            if ca < ACHROMATIC {
+                ha = hb;
+            } else if cb < ACHROMATIC {
+                hb = ha;
+            }
+            let l = a.l + (b.l - a.l) * t;
+            let c = ca + (cb - ca) * t;
+            let h = ha + hue_delta(ha, hb, hue_mode) * t;
+            Oklab::new(l, c * h.cos(), c * h.sin())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hue_modes() {
+        let (h0, h1) = (10_f32.to_radians(), 350_f32.to_radians());
+        let degrees = |mode| hue_delta(h0, h1, mode).to_degrees();
+        assert!((degrees(HueMode::Shorter) + 20.).abs() < 1e-3);
+        assert!((degrees(HueMode::Longer) - 340.).abs() < 1e-3);
+        assert!((degrees(HueMode::Increasing) - 340.).abs() < 1e-3);
+        assert!((degrees(HueMode::Decreasing) + 20.).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,216 @@
+use eframe::{egui, epaint::Color32};
+use palette::{convert::FromColorUnclamped, LinSrgb, Oklab, Srgb};
+use strum::IntoEnumIterator;
+
+use crate::{
+    color_spaces::{cielab_to_oklab, oklab_to_cielab, Cam16, ViewingConditions},
+    designer::Designer,
+    interpolation::{self, HueMode, Space},
+    lab_ui::LabUi,
+    plot::LinePlot,
+    utils::{delta_e_ok, oklab_clipped, oklab_to_srgb_clipped, srgb_to_color32},
+};
+
+/// A way of interpolating between two colors.
+#[derive(Debug, PartialEq, Copy, Clone)]
+enum Method {
+    Srgb,
+    LinearSrgb,
+    Cielab,
+    Oklab,
+    Oklch(HueMode),
+    Cam16Ucs,
+}
+
+impl Method {
+    fn all() -> Vec<Method> {
+        [
+            Method::Srgb,
+            Method::LinearSrgb,
+            Method::Cielab,
+            Method::Oklab,
+        ]
+        .into_iter()
+        .chain(HueMode::iter().map(Method::Oklch))
+        .chain([Method::Cam16Ucs])
+        .collect()
+    }
+
+    fn label(self) -> String {
+        match self {
+            Method::Srgb => "sRGB".to_owned(),
+            Method::LinearSrgb => "linear sRGB".to_owned(),
+            Method::Cielab => "CIELAB".to_owned(),
+            Method::Oklab => "Oklab".to_owned(),
+            Method::Oklch(mode) => format!("Oklch {:?}", mode).to_lowercase(),
+            Method::Cam16Ucs => "CAM16-UCS".to_owned(),
+        }
+    }
+
+    fn interpolate(self, a: Oklab, b: Oklab, t: f32, cam: &Cam16) -> Oklab {
+        match self {
+            Method::Srgb => {
+                let (a, b) = (Srgb::from_color_unclamped(a), Srgb::from_color_unclamped(b));
+                let c = Srgb::new(
+                    a.red + (b.red - a.red) * t,
+                    a.green + (b.green - a.green) * t,
+                    a.blue + (b.blue - a.blue) * t,
+                );
+                Oklab::from_color_unclamped(c)
+            }
+            Method::LinearSrgb => {
+                let (a, b) = (
+                    LinSrgb::from_color_unclamped(a),
+                    LinSrgb::from_color_unclamped(b),
+                );
+                let c = LinSrgb::new(
+                    a.red + (b.red - a.red) * t,
+                    a.green + (b.green - a.green) * t,
+                    a.blue + (b.blue - a.blue) * t,
+                );
+                Oklab::from_color_unclamped(c)
+            }
+            Method::Cielab => cielab_to_oklab(oklab_to_cielab(a).lerp(oklab_to_cielab(b), t)),
+            Method::Oklab => interpolation::interpolate(a, b, t, Space::Oklab, HueMode::default()),
+            Method::Oklch(mode) => interpolation::interpolate(a, b, t, Space::Oklch, mode),
+            Method::Cam16Ucs => cam.ucs_to_oklab(cam.oklab_to_ucs(a).lerp(cam.oklab_to_ucs(b), t)),
+        }
+    }
+}
+
+struct Strip {
+    method: Method,
+    texture: egui::TextureHandle,
+    /// ΔEOK between neighbouring samples, scaled to be per unit of the whole strip
+    delta_e: Vec<f32>,
+}
+
+/// The same colors interpolated in several spaces, to compare how even the results look.
+pub struct InterpolationComparison {
+    pub open: bool,
+    colors: Vec<Oklab>,
+    /// the strips, along with the colors they were made for
+    strips: Option<(Vec<Oklab>, Vec<Strip>)>,
+    cam: Cam16,
+}
+
+impl InterpolationComparison {
+    const STEPS: usize = 256;
+    const STRIP_HEIGHT: f32 = 24.;
+    const PLOT_HEIGHT: f32 = 40.;
+    /// colors taken from the design by "from design"
+    const DESIGN_COLORS: usize = 3;
+
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            colors: vec![Oklab::new(0.45, -0.03, -0.2), Oklab::new(0.9, 0.0, 0.17)],
+            strips: None,
+            cam: Cam16::new(&ViewingConditions::default()),
+        }
+    }
+
+    pub fn show_window(&mut self, ctx: &egui::Context, designer: &dyn Designer) {
+        let mut open = self.open;
+        egui::Window::new("interpolation comparison")
+            .open(&mut open)
+            .default_width(400.)
+            .show(ctx, |ui| self.ui(ui, designer));
+        self.open = open;
+    }
+
+    /// Sample `method` along all colors, with every segment getting the same share of the strip.
+    fn samples(&self, method: Method, colors: &[Oklab]) -> Vec<Oklab> {
+        let segments = colors.len() - 1;
+        (0..Self::STEPS)
+            .map(|i| {
+                let f = i as f32 / (Self::STEPS - 1) as f32 * segments as f32;
+                let s = (f as usize).min(segments - 1);
+                let lab = method.interpolate(colors[s], colors[s + 1], f - s as f32, &self.cam);
+                oklab_clipped(lab)
+            })
+            .collect()
+    }
+
+    fn make_strips(&self, ctx: &egui::Context) -> Vec<Strip> {
+        // all methods start from the same in gamut colors
+        let colors: Vec<Oklab> = self.colors.iter().map(|&c| oklab_clipped(c)).collect();
+        Method::all()
+            .into_iter()
+            .map(|method| {
+                let samples = self.samples(method, &colors);
+                let image = egui::ColorImage {
+                    size: [Self::STEPS, 1],
+                    pixels: samples
+                        .iter()
+                        .map(|&lab| srgb_to_color32(oklab_to_srgb_clipped(lab)))
+                        .collect(),
+                };
+                Strip {
+                    method,
+                    texture: ctx.load_texture(method.label(), image, egui::TextureOptions::LINEAR),
+                    delta_e: samples
+                        .windows(2)
+                        .map(|w| delta_e_ok(w[0], w[1]) * (Self::STEPS - 1) as f32)
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, designer: &dyn Designer) {
+        let mut remove = None;
+        for (i, color) in self.colors.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.add(LabUi::new(color, &format!("color {}", i + 1)));
+                if ui.button("🗑").clicked() {
+                    remove = Some(i);
+                }
+            });
+        }
+        if let Some(i) = remove {
+            if self.colors.len() > 2 {
+                self.colors.remove(i);
+            }
+        }
+        ui.horizontal(|ui| {
+            if ui.button("+ color").clicked() {
+                self.colors.push(*self.colors.last().unwrap());
+            }
+            if ui
+                .button("from design")
+                .on_hover_text("take colors from along the x axis of the design")
+                .clicked()
+            {
+                self.colors = (0..Self::DESIGN_COLORS)
+                    .map(|i| designer.sample(i as f32 / (Self::DESIGN_COLORS - 1) as f32, 0.5))
+                    .collect();
+            }
+        });
+        ui.separator();
+        if !matches!(&self.strips, Some((colors, _)) if *colors == self.colors) {
+            self.strips = Some((self.colors.clone(), self.make_strips(ui.ctx())));
+        }
+        let Some((_, strips)) = &self.strips else {
+            return;
+        };
+        // the same scale for all plots, so they can be compared
+        let max = strips
+            .iter()
+            .flat_map(|s| s.delta_e.iter())
+            .fold(0_f32, |a, &b| a.max(b));
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for strip in strips {
+                ui.label(strip.method.label());
+                let width = ui.available_width();
+                ui.image((strip.texture.id(), egui::vec2(width, Self::STRIP_HEIGHT)));
+                ui.add(
+                    LinePlot::new("ΔEOK")
+                        .height(Self::PLOT_HEIGHT)
+                        .y_range(0. ..=max.max(1e-3))
+                        .series(&strip.delta_e, Color32::GRAY),
+                );
+            }
+        });
+    }
+}
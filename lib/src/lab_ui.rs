@@ -3,9 +3,13 @@ use std::ops::RangeInclusive;
 use eframe::egui::{self, Widget};
 use palette::Oklab;
 
-use crate::utils::{resettable_slider_raw, NEUTRAL_LAB};
+use crate::{
+    chroma_pad::ChromaPad,
+    color_picker::ColorPicker,
+    utils::{color_slider_raw, resettable_slider_raw, NEUTRAL_LAB},
+    working_space::WorkingSpace,
+};
 
-// TODO make this show a color picker?
 pub struct LabUi<'a> {
     lab: &'a mut Oklab,
     label: &'a str,
@@ -13,6 +17,11 @@ pub struct LabUi<'a> {
     a_range: RangeInclusive<f32>,
     b_range: RangeInclusive<f32>,
     default_value: Oklab,
+    /// offer the color picker and a/b pad and show colors on the sliders,
+    /// off for values that are not colors
+    picker: bool,
+    /// the space the value is a coordinate in, if not Oklab
+    space: Option<&'a WorkingSpace>,
 }
 
 impl<'a> LabUi<'a> {
@@ -24,6 +33,8 @@ impl<'a> LabUi<'a> {
             a_range: Oklab::<f32>::min_a()..=Oklab::<f32>::max_a(),
             b_range: Oklab::<f32>::min_b()..=Oklab::<f32>::max_b(),
             default_value: NEUTRAL_LAB,
+            picker: true,
+            space: None,
         }
     }
 
@@ -48,21 +59,126 @@ impl<'a> LabUi<'a> {
         self.default_value = default_value;
         self
     }
+
+    pub fn picker(mut self, picker: bool) -> Self {
+        self.picker = picker;
+        self
+    }
+
+    /// Show and pick colors as they are in `space`.
+    pub fn space(mut self, space: &'a WorkingSpace) -> Self {
+        self.space = Some(space);
+        self
+    }
 }
 
 impl<'a> Widget for LabUi<'a> {
     fn ui(self, ui: &mut egui::Ui) -> egui::Response {
-        egui::Grid::new(self.label)
+        let space = self.space;
+        let to_oklab = |lab: Oklab| space.map_or(lab, |s| s.to_oklab(lab));
+        // the gamut boundary of the pad is only right in Oklab
+        let pad = self.picker && !matches!(space, Some(s) if !s.is_oklab());
+        let picker_id = ui.make_persistent_id(self.label).with("picker");
+        let [mut picker_open, mut pad_open] = if self.picker {
+            ui.data_mut(|d| d.get_temp(picker_id)).unwrap_or_default()
+        } else {
+            [false; 2]
+        };
+        let mut response = egui::Grid::new(self.label)
             .show(ui, |ui| {
                 ui.label(self.label);
+                if self.picker {
+                    ui.horizontal(|ui| {
+                        ui.toggle_value(&mut picker_open, "🎨")
+                            .on_hover_text("color picker");
+                        if pad {
+                            ui.toggle_value(&mut pad_open, "◎")
+                                .on_hover_text("a/b plane at this lightness");
+                        }
+                    });
+                }
                 ui.end_row();
-                resettable_slider_raw(ui, &mut self.lab.l, "L", self.l_range, self.default_value.l);
+                let lab = *self.lab;
+                let color = self.picker;
+                component_slider(
+                    ui,
+                    &mut self.lab.l,
+                    "L",
+                    self.l_range,
+                    self.default_value.l,
+                    color.then_some(|l| to_oklab(Oklab { l, ..lab })),
+                );
                 ui.end_row();
-                resettable_slider_raw(ui, &mut self.lab.a, "a", self.a_range, self.default_value.a);
+                component_slider(
+                    ui,
+                    &mut self.lab.a,
+                    "a",
+                    self.a_range.clone(),
+                    self.default_value.a,
+                    color.then_some(|a| to_oklab(Oklab { a, ..lab })),
+                );
                 ui.end_row();
-                resettable_slider_raw(ui, &mut self.lab.b, "b", self.b_range, self.default_value.b);
+                component_slider(
+                    ui,
+                    &mut self.lab.b,
+                    "b",
+                    self.b_range.clone(),
+                    self.default_value.b,
+                    color.then_some(|b| to_oklab(Oklab { b, ..lab })),
+                );
                 ui.end_row();
             })
-            .response
+            .response;
+        if picker_open {
+            let mut color = to_oklab(*self.lab);
+            let picker = ui.add(ColorPicker::new(&mut color, picker_id));
+            if picker.changed() {
+                *self.lab = space.map_or(color, |s| s.coordinates(color));
+            }
+            response |= picker;
+        }
+        if pad && pad_open {
+            response |= ui.add(ChromaPad::new(self.lab, self.a_range, self.b_range));
+        }
+        if self.picker {
+            ui.data_mut(|d| d.insert_temp(picker_id, [picker_open, pad_open]));
+        }
+        response
+    }
+}
+
+/// A slider showing the colors along it if there is a `color` for each value.
+fn component_slider(
+    ui: &mut egui::Ui,
+    value: &mut f32,
+    text: &str,
+    range: RangeInclusive<f32>,
+    default_value: f32,
+    color: Option<impl Fn(f32) -> Oklab>,
+) {
+    match color {
+        Some(color) => color_slider_raw(ui, value, text, range, default_value, color),
+        None => resettable_slider_raw(ui, value, text, range, default_value),
+    }
+}
+
+/// Edit a color as lightness, chroma and hue.
+pub fn lch_ui(ui: &mut egui::Ui, lab: &mut Oklab) {
+    let mut l = lab.l;
+    let mut chroma = lab.a.hypot(lab.b);
+    let mut hue = lab.b.atan2(lab.a).to_degrees().rem_euclid(360.);
+    // only write back on change, the round trip is not exact
+    let mut changed = ui
+        .add(egui::Slider::new(&mut l, 0. ..=1.).text("L"))
+        .changed();
+    changed |= ui
+        .add(egui::Slider::new(&mut chroma, 0. ..=0.4).text("C"))
+        .changed();
+    changed |= ui
+        .add(egui::Slider::new(&mut hue, 0. ..=360.).text("h"))
+        .changed();
+    if changed {
+        let (b, a) = hue.to_radians().sin_cos();
+        *lab = Oklab::new(l, chroma * a, chroma * b);
     }
 }
@@ -1,17 +1,56 @@
 mod bent_gradient;
 mod blur;
+mod canvas;
+mod categorical_gradient;
+mod chroma_pad;
+mod color_picker;
+mod color_spaces;
+mod colormap;
+mod colormap_analysis;
+mod contrast;
+mod cubehelix_gradient;
+mod cvd;
 mod designer;
+mod diverging_gradient;
 mod gamut_mapping;
+mod gamut_slice;
 mod hue_gradient;
+mod image_palette_gradient;
+mod inspector;
+mod interpolation;
+mod interpolation_comparison;
 mod lab_ui;
 mod linear_gradient;
+mod mesh_gradient;
+mod multi_stop_gradient;
+mod noise;
+mod noise_gradient;
+mod okhsv;
+mod plot;
+mod point_cloud;
+mod polar_gradient;
+mod probe;
 mod rotator;
+mod sequential_gradient;
 mod space_filling_gradient;
 mod space_filling_gradient_2;
+mod spline_gradient;
+mod tonal_gradient;
 mod utils;
-use crate::designer::Designer;
+mod working_space;
+use crate::{
+    canvas::Canvas,
+    colormap_analysis::ColormapAnalysis,
+    contrast::Contrast,
+    designer::Designer,
+    gamut_slice::GamutSlice,
+    interpolation_comparison::InterpolationComparison,
+    point_cloud::PointCloud,
+    probe::Probe,
+    utils::{save_png, UNIT_RECT},
+};
 use eframe::{egui, App};
-use native_dialog::{FileDialog, MessageDialog, MessageType};
+use native_dialog::FileDialog;
 use palette::Srgb;
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
@@ -26,6 +65,18 @@ enum DesignerType {
     #[default]
     SpaceFilling,
     SpaceFilling2,
+    MultiStop,
+    Radial,
+    Conic,
+    Mesh,
+    Spline,
+    Noise,
+    ImagePalette,
+    Categorical,
+    Tonal,
+    Diverging,
+    Sequential,
+    Cubehelix,
 }
 
 impl DesignerType {
@@ -36,62 +87,61 @@ impl DesignerType {
             DesignerType::Bent => Box::new(bent_gradient::Gradient::new()),
             DesignerType::SpaceFilling => Box::new(space_filling_gradient::Gradient::new()),
             DesignerType::SpaceFilling2 => Box::new(space_filling_gradient_2::Gradient::new()),
+            DesignerType::MultiStop => Box::new(multi_stop_gradient::Gradient::new()),
+            DesignerType::Radial => Box::new(polar_gradient::Gradient::radial()),
+            DesignerType::Conic => Box::new(polar_gradient::Gradient::conic()),
+            DesignerType::Mesh => Box::new(mesh_gradient::Gradient::new()),
+            DesignerType::Spline => Box::new(spline_gradient::Gradient::new()),
+            DesignerType::Noise => Box::new(noise_gradient::Gradient::new()),
+            DesignerType::ImagePalette => Box::new(image_palette_gradient::Gradient::new()),
+            DesignerType::Categorical => Box::new(categorical_gradient::Gradient::new()),
+            DesignerType::Tonal => Box::new(tonal_gradient::Gradient::new()),
+            DesignerType::Diverging => Box::new(diverging_gradient::Gradient::new()),
+            DesignerType::Sequential => Box::new(sequential_gradient::Gradient::new()),
+            DesignerType::Cubehelix => Box::new(cubehelix_gradient::Gradient::new()),
         }
     }
 }
 
-fn make_texture_from_params(
-    ctx: &eframe::egui::Context,
-    designer: &dyn Designer,
-) -> egui::TextureHandle {
-    // TODO don't create intermediate buffer somehow?
-    let mut buf = vec![Srgb::default(); IMG_SIZE * IMG_SIZE];
-    designer.render((IMG_SIZE, IMG_SIZE), &mut buf);
-    let u8buf: Vec<u8> = buf
-        .iter()
-        .flat_map(|p| {
-            let q = p.into_format();
-            [q.red, q.green, q.blue, u8::MAX]
-        })
-        .collect();
-    ctx.load_texture(
-        "gradient",
-        egui::ColorImage::from_rgba_unmultiplied([IMG_SIZE, IMG_SIZE], u8buf.as_ref()),
-        egui::TextureOptions::default(),
-    )
+/// What clicking on the preview does.
+#[derive(EnumIter, Debug, PartialEq, Default, Copy, Clone)]
+enum Tool {
+    /// copy the color under the pointer
+    #[default]
+    Inspect,
+    /// add points to the probe line
+    Probe,
+    /// pick the text and background colors to compare
+    Contrast,
 }
 
 fn save_image_from_params<P: AsRef<std::path::Path>>(designer: &dyn Designer, path: P) {
     let mut buf = vec![Srgb::default(); IMG_SIZE * IMG_SIZE];
-    designer.render((IMG_SIZE, IMG_SIZE), &mut buf);
-    let u16buf: Vec<u16> = buf
-        .iter()
-        .flat_map(|p| {
-            let q = p.into_format();
-            [q.red, q.green, q.blue, u16::MAX]
-        })
-        .collect();
-    if let Err(e) = image::ImageBuffer::<image::Rgba<u16>, Vec<u16>>::from_vec(
-        IMG_SIZE as u32,
-        IMG_SIZE as u32,
-        u16buf,
-    )
-    .unwrap()
-    .save(path)
-    {
-        MessageDialog::new()
-            .set_type(MessageType::Error)
-            .set_title("Error saving image")
-            .set_text(&e.to_string())
-            .show_alert()
-            .unwrap();
-    }
+    designer.render((IMG_SIZE, IMG_SIZE), UNIT_RECT, &mut buf);
+    save_png(&buf, (IMG_SIZE, IMG_SIZE), path);
 }
 
 pub struct Gui {
     // TODO keep hold of old designers to not loose params when switching
     current_designer: (DesignerType, Box<dyn Designer>),
-    texture: Option<egui::TextureHandle>,
+    canvas: Canvas,
+    tool: Tool,
+    probe: Probe,
+    colormap_analysis: ColormapAnalysis,
+    gamut_slice: GamutSlice,
+    point_cloud: PointCloud,
+    contrast: Contrast,
+    interpolation_comparison: InterpolationComparison,
+}
+
+impl Gui {
+    fn designer_changed(&mut self) {
+        self.canvas.invalidate();
+        self.colormap_analysis.invalidate();
+        self.gamut_slice.invalidate();
+        self.point_cloud.invalidate();
+        self.contrast.invalidate();
+    }
 }
 
 impl Default for Gui {
@@ -100,7 +150,14 @@ impl Default for Gui {
         let designer = dtype.make();
         Self {
             current_designer: (dtype, designer),
-            texture: None,
+            canvas: Canvas::new(),
+            tool: Tool::default(),
+            probe: Probe::new(),
+            colormap_analysis: ColormapAnalysis::new(),
+            gamut_slice: GamutSlice::new(),
+            point_cloud: PointCloud::new(),
+            contrast: Contrast::new(),
+            interpolation_comparison: InterpolationComparison::new(),
         }
     }
 }
@@ -119,7 +176,7 @@ impl App for Gui {
                         if selected_designer != self.current_designer.0 {
                             let new_designer = selected_designer.make();
                             self.current_designer = (selected_designer, new_designer);
-                            self.texture = None;
+                            self.designer_changed();
                         }
                     });
                 ui.separator();
@@ -131,20 +188,59 @@ impl App for Gui {
                         save_image_from_params(self.current_designer.1.as_ref(), path);
                     }
                 }
+                ui.separator();
+                self.canvas.show_options(ui);
+                ui.separator();
+                for tool in Tool::iter() {
+                    ui.selectable_value(&mut self.tool, tool, format!("{:?}", tool));
+                }
+                ui.separator();
+                ui.menu_button("views", |ui| {
+                    ui.checkbox(&mut self.probe.open, "probe profile");
+                    ui.checkbox(&mut self.colormap_analysis.open, "colormap analysis");
+                    ui.checkbox(&mut self.gamut_slice.open, "gamut slice");
+                    ui.checkbox(&mut self.point_cloud.open, "oklab 3d");
+                    ui.checkbox(&mut self.contrast.open, "contrast");
+                    ui.checkbox(
+                        &mut self.interpolation_comparison.open,
+                        "interpolation comparison",
+                    );
+                });
             });
         });
         egui::SidePanel::left("left panel").show(ctx, |ui| {
             ui.set_min_width(250.);
             egui::ScrollArea::vertical().show(ui, |ui| {
-                if self.current_designer.1.show_ui(ui) || self.texture.is_none() {
-                    let tex = make_texture_from_params(ctx, self.current_designer.1.as_ref());
-                    self.texture = Some(tex);
+                if self.current_designer.1.show_ui(ui) {
+                    self.designer_changed();
                 }
             });
         });
         egui::CentralPanel::default().show(ctx, |ui| {
-            let texture = self.texture.as_ref().unwrap();
-            ui.image(texture);
+            let designer = self.current_designer.1.as_ref();
+            let response = self.canvas.show(ui, designer);
+            let painter = ui.painter_at(response.rect);
+            self.probe
+                .show_on_canvas(&self.canvas, &response, &painter, self.tool == Tool::Probe);
+            self.contrast.show_on_canvas(
+                &self.canvas,
+                &response,
+                &painter,
+                self.tool == Tool::Contrast,
+            );
+            inspector::show(
+                &self.canvas,
+                &response,
+                designer,
+                self.tool == Tool::Inspect,
+            );
         });
+        let designer = self.current_designer.1.as_ref();
+        self.probe.show_window(ctx, designer);
+        self.colormap_analysis.show_window(ctx, designer);
+        self.gamut_slice.show_window(ctx, designer);
+        self.point_cloud.show_window(ctx, designer);
+        self.contrast.show_window(ctx, &self.canvas, designer);
+        self.interpolation_comparison.show_window(ctx, designer);
     }
 }
@@ -1,10 +1,12 @@
 use crate::{
     designer,
+    lab_ui::LabUi,
     utils::{
         oklab_to_srgb, oklab_to_srgb_clipped, oklab_to_vec3, render_par, vec3_to_oklab, NEUTRAL_LAB,
     },
+    working_space::WorkingSpace,
 };
-use eframe::egui;
+use eframe::{egui, emath::Rect};
 use palette::{Oklab, Srgb};
 
 #[derive(PartialEq, Clone)]
@@ -12,6 +14,7 @@ pub struct Gradient {
     center: Oklab,
     x_slope: Oklab,
     y_slope: Oklab,
+    space: WorkingSpace,
     extend: bool,
 }
 
@@ -21,6 +24,7 @@ impl Gradient {
             center: NEUTRAL_LAB,
             x_slope: Oklab::default(),
             y_slope: Oklab::default(),
+            space: WorkingSpace::new(),
             extend: true,
         }
     }
@@ -33,18 +37,12 @@ impl designer::Designer for Gradient {
             center,
             x_slope,
             y_slope,
+            space,
             extend,
         } = &mut c;
         ui.vertical(|ui| {
-            ui.add(
-                egui::Slider::new(&mut center.l, Oklab::min_l()..=Oklab::max_l()).text("L center"),
-            );
-            ui.add(
-                egui::Slider::new(&mut center.a, Oklab::min_a()..=Oklab::max_a()).text("a center"),
-            );
-            ui.add(
-                egui::Slider::new(&mut center.b, Oklab::min_b()..=Oklab::max_b()).text("b center"),
-            );
+            space.show_ui(ui);
+            ui.add(LabUi::new(center, "center").space(space));
             ui.add(egui::Slider::new(&mut x_slope.l, -1f32..=1.).text("L x slope"));
             ui.add(egui::Slider::new(&mut x_slope.b, -1f32..=1.).text("b x slope"));
             ui.add(egui::Slider::new(&mut x_slope.a, -1f32..=1.).text("a x slope"));
@@ -66,15 +64,19 @@ impl designer::Designer for Gradient {
         }
     }
 
-    fn render(&self, size: (usize, usize), buf: &mut [Srgb]) {
-        render_par(size, buf, |x, y| {
-            let xcenter = x - 0.5;
-            let ycenter = y - 0.5;
-            let lab = vec3_to_oklab(
-                oklab_to_vec3(self.center)
-                    + xcenter * oklab_to_vec3(self.x_slope)
-                    + ycenter * oklab_to_vec3(self.y_slope),
-            );
+    fn sample(&self, x: f32, y: f32) -> Oklab {
+        let xcenter = x - 0.5;
+        let ycenter = y - 0.5;
+        self.space.to_oklab(vec3_to_oklab(
+            oklab_to_vec3(self.center)
+                + xcenter * oklab_to_vec3(self.x_slope)
+                + ycenter * oklab_to_vec3(self.y_slope),
+        ))
+    }
+
+    fn render(&self, size: (usize, usize), region: Rect, buf: &mut [Srgb]) {
+        render_par(size, region, buf, |x, y| {
+            let lab = self.sample(x, y);
             if self.extend {
                 oklab_to_srgb_clipped(lab)
             } else {
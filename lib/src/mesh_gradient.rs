@@ -0,0 +1,195 @@
+use crate::{
+    designer::{self, Designer},
+    interpolation::catmull_rom,
+    lab_ui::LabUi,
+    utils::{
+        oklab_to_srgb, oklab_to_srgb_clipped, oklab_to_vec3, render_par, resettable_slider,
+        smooth_par, srgb_to_color32, vec3_to_oklab,
+    },
+};
+use eframe::{
+    egui::{self, Sense},
+    emath::{vec2, Rect},
+};
+use glam::Vec3;
+use palette::{Oklab, Srgb};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+#[derive(EnumIter, Debug, PartialEq, Default, Copy, Clone)]
+enum Interpolation {
+    #[default]
+    Bilinear,
+    /// catmull-rom through the control colors
+    Bicubic,
+}
+
+/// A grid of control colors blended across the image.
+#[derive(PartialEq, Clone)]
+pub struct Gradient {
+    columns: usize,
+    rows: usize,
+    /// row major
+    colors: Vec<Oklab>,
+    interpolation: Interpolation,
+    extend: bool,
+    smooth: f32,
+}
+
+impl Gradient {
+    const SIZE_DEFAULT: usize = 2;
+    const SMOOTH_DEFAULT: f32 = 0.;
+    const SWATCH_SIZE: f32 = 20.;
+
+    pub fn new() -> Self {
+        Self {
+            columns: Self::SIZE_DEFAULT,
+            rows: Self::SIZE_DEFAULT,
+            colors: vec![
+                Oklab::new(0.85, 0.08, 0.08),
+                Oklab::new(0.85, -0.05, 0.12),
+                Oklab::new(0.45, -0.02, -0.15),
+                Oklab::new(0.45, 0.12, -0.08),
+            ],
+            interpolation: Interpolation::default(),
+            extend: true,
+            smooth: Self::SMOOTH_DEFAULT,
+        }
+    }
+
+    /// Control color with the indices clamped to the mesh.
+    fn control(&self, column: isize, row: isize) -> Vec3 {
+        let column = column.clamp(0, self.columns as isize - 1) as usize;
+        let row = row.clamp(0, self.rows as isize - 1) as usize;
+        oklab_to_vec3(self.colors[row * self.columns + column])
+    }
+
+    /// Change the size of the mesh, keeping the look of the design.
+    fn resized(&self, columns: usize, rows: usize) -> Self {
+        Self {
+            columns,
+            rows,
+            colors: (0..rows * columns)
+                .map(|i| {
+                    self.sample(
+                        (i % columns) as f32 / (columns - 1) as f32,
+                        (i / columns) as f32 / (rows - 1) as f32,
+                    )
+                })
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    /// Clickable swatches of the control colors, laid out like the mesh.
+    fn swatches(&self, ui: &mut egui::Ui, selected: &mut usize) {
+        for row in 0..self.rows {
+            ui.horizontal(|ui| {
+                for column in 0..self.columns {
+                    let i = row * self.columns + column;
+                    let (response, painter) = ui.allocate_painter(
+                        vec2(Self::SWATCH_SIZE, Self::SWATCH_SIZE),
+                        Sense::click(),
+                    );
+                    if response.clicked() {
+                        *selected = i;
+                    }
+                    let visuals = ui.style().interact_selectable(&response, i == *selected);
+                    painter.rect(
+                        response.rect,
+                        2.,
+                        srgb_to_color32(oklab_to_srgb_clipped(self.colors[i])),
+                        visuals.fg_stroke,
+                    );
+                }
+            });
+        }
+    }
+}
+
+impl designer::Designer for Gradient {
+    fn show_ui(&mut self, ui: &mut eframe::egui::Ui) -> bool {
+        let mut c = self.clone();
+        // which control color is being edited is ui state, not part of the design
+        let selected_id = ui.id().with("selected control color");
+        let mut selected = ui.data(|d| d.get_temp::<usize>(selected_id).unwrap_or(0));
+        ui.vertical(|ui| {
+            let (mut columns, mut rows) = (c.columns, c.rows);
+            resettable_slider(ui, &mut columns, "columns", 2..=6, Self::SIZE_DEFAULT);
+            resettable_slider(ui, &mut rows, "rows", 2..=6, Self::SIZE_DEFAULT);
+            if (columns, rows) != (c.columns, c.rows) {
+                c = c.resized(columns, rows);
+            }
+            selected = selected.min(c.colors.len() - 1);
+            c.swatches(ui, &mut selected);
+            let Gradient {
+                colors,
+                interpolation,
+                extend,
+                smooth,
+                ..
+            } = &mut c;
+            ui.add(LabUi::new(&mut colors[selected], "color"));
+            ui.horizontal(|ui| {
+                for i in Interpolation::iter() {
+                    ui.selectable_value(interpolation, i, format!("{:?}", i));
+                }
+            });
+            ui.checkbox(extend, "extend");
+            ui.add_enabled_ui(*extend, |ui| {
+                resettable_slider(ui, smooth, "smooth", 0. ..=100., Self::SMOOTH_DEFAULT);
+            });
+        });
+        ui.data_mut(|d| d.insert_temp(selected_id, selected));
+        if c != *self {
+            *self = c;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn sample(&self, x: f32, y: f32) -> Oklab {
+        // outside of the design the edges are extended
+        let gx = x.clamp(0., 1.) * (self.columns - 1) as f32;
+        let gy = y.clamp(0., 1.) * (self.rows - 1) as f32;
+        let column = (gx as isize).min(self.columns as isize - 2);
+        let row = (gy as isize).min(self.rows as isize - 2);
+        let (fx, fy) = (gx - column as f32, gy - row as f32);
+        let v = match self.interpolation {
+            Interpolation::Bilinear => {
+                let top = self
+                    .control(column, row)
+                    .lerp(self.control(column + 1, row), fx);
+                let bottom = self
+                    .control(column, row + 1)
+                    .lerp(self.control(column + 1, row + 1), fx);
+                top.lerp(bottom, fy)
+            }
+            Interpolation::Bicubic => {
+                let rows = [-1, 0, 1, 2].map(|dy| {
+                    catmull_rom(
+                        [-1, 0, 1, 2].map(|dx| self.control(column + dx, row + dy)),
+                        fx,
+                    )
+                });
+                catmull_rom(rows, fy)
+            }
+        };
+        vec3_to_oklab(v)
+    }
+
+    fn render(&self, size: (usize, usize), region: Rect, buf: &mut [Srgb]) {
+        render_par(size, region, buf, |x, y| {
+            let lab = self.sample(x, y);
+            if self.extend {
+                oklab_to_srgb_clipped(lab)
+            } else {
+                oklab_to_srgb(&lab)
+            }
+        });
+        if self.smooth > 0. && self.extend {
+            smooth_par(size, region, buf, self.smooth);
+        }
+    }
+}
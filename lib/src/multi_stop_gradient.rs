@@ -0,0 +1,289 @@
+use std::{
+    f32::consts::{PI, TAU},
+    ops::RangeInclusive,
+};
+
+use crate::{
+    designer,
+    interpolation::{interpolate, Easing, HueMode, Space},
+    lab_ui::{lch_ui, LabUi},
+    utils::{oklab_to_srgb, oklab_to_srgb_clipped, render_par, resettable_slider, srgb_to_color32},
+};
+use eframe::{
+    egui::{self, Sense},
+    emath::{pos2, vec2, Rect},
+    epaint::{Shape, Stroke},
+};
+use palette::{Oklab, Srgb};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+/// How the gradient parameter is derived from the position in the image.
+#[derive(EnumIter, Debug, PartialEq, Default, Copy, Clone)]
+enum Layout {
+    #[default]
+    Linear,
+    Radial,
+    Conic,
+}
+
+#[derive(PartialEq, Clone, Copy)]
+struct Stop {
+    position: f32,
+    color: Oklab,
+    /// easing of the segment that starts at this stop
+    easing: Easing,
+}
+
+#[derive(PartialEq, Clone)]
+pub struct Gradient {
+    /// sorted by position
+    stops: Vec<Stop>,
+    space: Space,
+    hue_mode: HueMode,
+    layout: Layout,
+    angle: f32,
+    extend: bool,
+}
+
+impl Gradient {
+    const ANGLE_DEFAULT: f32 = 0.;
+    const BAR_HEIGHT: f32 = 24.;
+    const HANDLE_SIZE: f32 = 8.;
+
+    pub fn new() -> Self {
+        Self {
+            stops: vec![
+                Stop {
+                    position: 0.,
+                    color: Oklab::new(0.3, -0.03, -0.15),
+                    easing: Easing::default(),
+                },
+                Stop {
+                    position: 1.,
+                    color: Oklab::new(0.85, 0.05, 0.15),
+                    easing: Easing::default(),
+                },
+            ],
+            space: Space::default(),
+            hue_mode: HueMode::default(),
+            layout: Layout::default(),
+            angle: Self::ANGLE_DEFAULT,
+            extend: true,
+        }
+    }
+
+    /// Color at `t` along the stops.
+    fn color_at(&self, t: f32) -> Oklab {
+        let first = self.stops.first().unwrap();
+        let last = self.stops.last().unwrap();
+        if t <= first.position {
+            return first.color;
+        }
+        if t >= last.position {
+            return last.color;
+        }
+        let i = self.stops.partition_point(|s| s.position <= t).max(1) - 1;
+        let (a, b) = (self.stops[i], self.stops[i + 1]);
+        let f = if b.position > a.position {
+            (t - a.position) / (b.position - a.position)
+        } else {
+            0.
+        };
+        interpolate(
+            a.color,
+            b.color,
+            a.easing.apply(f),
+            self.space,
+            self.hue_mode,
+        )
+    }
+
+    /// The range that stop `i` can be moved in without passing its neighbours.
+    fn position_range(&self, i: usize) -> RangeInclusive<f32> {
+        let lower = if i > 0 {
+            self.stops[i - 1].position
+        } else {
+            0.
+        };
+        let upper = self.stops.get(i + 1).map_or(1., |s| s.position);
+        lower..=upper
+    }
+
+    /// The gradient with a draggable handle for each stop.
+    /// Double click the bar to add a stop, right click a handle to remove it.
+    /// Stops can't be dragged past each other, so they stay sorted.
+    fn stop_bar(&mut self, ui: &mut egui::Ui, selected: &mut usize) {
+        const PREVIEW_STEPS: usize = 128;
+        let (response, painter) = ui.allocate_painter(
+            vec2(ui.available_width(), Self::BAR_HEIGHT + Self::HANDLE_SIZE),
+            Sense::click(),
+        );
+        let bar = Rect::from_min_size(
+            response.rect.min,
+            vec2(response.rect.width(), Self::BAR_HEIGHT),
+        );
+        let w = bar.width() / PREVIEW_STEPS as f32;
+        for i in 0..PREVIEW_STEPS {
+            let x = bar.left() + i as f32 * w;
+            painter.rect_filled(
+                // overlap slightly to avoid seams
+                Rect::from_x_y_ranges(x..=x + w + 0.5, bar.y_range()),
+                0.,
+                srgb_to_color32(oklab_to_srgb_clipped(
+                    self.color_at((i as f32 + 0.5) / PREVIEW_STEPS as f32),
+                )),
+            );
+        }
+        if response.double_clicked() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                let position = ((pos.x - bar.left()) / bar.width()).clamp(0., 1.);
+                let i = self.stops.partition_point(|s| s.position <= position);
+                self.stops.insert(
+                    i,
+                    Stop {
+                        position,
+                        color: self.color_at(position),
+                        easing: Easing::default(),
+                    },
+                );
+                *selected = i;
+            }
+        }
+        let mut remove = None;
+        for i in 0..self.stops.len() {
+            let range = self.position_range(i);
+            let stop = &mut self.stops[i];
+            let x = bar.left() + stop.position * bar.width();
+            let handle = Rect::from_center_size(
+                pos2(x, bar.bottom() + Self::HANDLE_SIZE / 2.),
+                vec2(Self::HANDLE_SIZE * 2., Self::HANDLE_SIZE),
+            );
+            let handle_response = ui.interact(handle, response.id.with(i), Sense::click_and_drag());
+            if handle_response.dragged() {
+                stop.position = (stop.position + handle_response.drag_delta().x / bar.width())
+                    .clamp(*range.start(), *range.end());
+            }
+            if handle_response.clicked() || handle_response.drag_started() {
+                *selected = i;
+            }
+            if handle_response.secondary_clicked() {
+                remove = Some(i);
+            }
+            let visuals = ui
+                .style()
+                .interact_selectable(&handle_response, i == *selected);
+            painter.add(Shape::convex_polygon(
+                vec![
+                    pos2(x, bar.bottom()),
+                    handle.right_bottom(),
+                    handle.left_bottom(),
+                ],
+                srgb_to_color32(oklab_to_srgb_clipped(stop.color)),
+                Stroke::new(
+                    if i == *selected { 2. } else { 1. },
+                    visuals.fg_stroke.color,
+                ),
+            ));
+        }
+        if let Some(i) = remove {
+            if self.stops.len() > 2 {
+                self.stops.remove(i);
+                *selected = selected.saturating_sub(usize::from(*selected >= i));
+            }
+        }
+    }
+}
+
+impl designer::Designer for Gradient {
+    fn show_ui(&mut self, ui: &mut eframe::egui::Ui) -> bool {
+        let mut c = self.clone();
+        // which stop is being edited is ui state, not part of the design
+        let selected_id = ui.id().with("selected stop");
+        let mut selected = ui.data(|d| d.get_temp::<usize>(selected_id).unwrap_or(0));
+        ui.vertical(|ui| {
+            c.stop_bar(ui, &mut selected);
+            selected = selected.min(c.stops.len() - 1);
+            let last = selected == c.stops.len() - 1;
+            let range = c.position_range(selected);
+            let Gradient {
+                stops,
+                space,
+                hue_mode,
+                layout,
+                angle,
+                extend,
+            } = &mut c;
+            let stop = &mut stops[selected];
+            ui.add(egui::Slider::new(&mut stop.position, range).text("position"));
+            match space {
+                Space::Oklab => {
+                    ui.add(LabUi::new(&mut stop.color, "color"));
+                }
+                Space::Oklch => lch_ui(ui, &mut stop.color),
+            }
+            ui.add_enabled_ui(!last, |ui| {
+                egui::ComboBox::from_label("easing")
+                    .selected_text(format!("{:?}", stop.easing))
+                    .show_ui(ui, |ui| {
+                        for easing in Easing::iter() {
+                            ui.selectable_value(&mut stop.easing, easing, format!("{:?}", easing));
+                        }
+                    });
+            });
+            ui.separator();
+            ui.horizontal(|ui| {
+                for s in Space::iter() {
+                    ui.selectable_value(space, s, format!("{:?}", s));
+                }
+            });
+            ui.add_enabled_ui(*space == Space::Oklch, |ui| {
+                egui::ComboBox::from_label("hue")
+                    .selected_text(format!("{:?}", hue_mode))
+                    .show_ui(ui, |ui| {
+                        for mode in HueMode::iter() {
+                            ui.selectable_value(hue_mode, mode, format!("{:?}", mode));
+                        }
+                    });
+            });
+            ui.horizontal(|ui| {
+                for l in Layout::iter() {
+                    ui.selectable_value(layout, l, format!("{:?}", l));
+                }
+            });
+            ui.add_enabled_ui(*layout != Layout::Radial, |ui| {
+                resettable_slider(ui, angle, "angle", -PI..=PI, Self::ANGLE_DEFAULT);
+            });
+            ui.checkbox(extend, "extend");
+        });
+        ui.data_mut(|d| d.insert_temp(selected_id, selected));
+        if c != *self {
+            *self = c;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn sample(&self, x: f32, y: f32) -> Oklab {
+        let p = vec2(x - 0.5, y - 0.5);
+        let t = match self.layout {
+            Layout::Linear => p.dot(egui::Vec2::angled(self.angle)) + 0.5,
+            // 1 at the middle of the edges
+            Layout::Radial => p.length() * 2.,
+            Layout::Conic => ((p.angle() - self.angle) / TAU).rem_euclid(1.),
+        };
+        self.color_at(t)
+    }
+
+    fn render(&self, size: (usize, usize), region: Rect, buf: &mut [Srgb]) {
+        render_par(size, region, buf, |x, y| {
+            let lab = self.sample(x, y);
+            if self.extend {
+                oklab_to_srgb_clipped(lab)
+            } else {
+                oklab_to_srgb(&lab)
+            }
+        });
+    }
+}
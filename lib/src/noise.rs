@@ -0,0 +1,149 @@
+use glam::{vec2, Vec2};
+use strum_macros::EnumIter;
+
+/// Ways of combining simplex noise.
+#[derive(EnumIter, Debug, PartialEq, Default, Copy, Clone)]
+pub enum Kind {
+    Simplex,
+    /// several octaves of simplex noise summed
+    #[default]
+    Fbm,
+    /// fbm evaluated at a position that is offset by two more fbm fields
+    DomainWarp,
+}
+
+#[derive(PartialEq, Clone, Copy)]
+pub struct Params {
+    pub kind: Kind,
+    pub octaves: u32,
+    /// frequency multiplier between octaves
+    pub lacunarity: f32,
+    /// amplitude multiplier between octaves
+    pub gain: f32,
+    /// how far domain warping moves the sample position
+    pub warp: f32,
+}
+
+const D: f32 = std::f32::consts::FRAC_1_SQRT_2;
+const GRADIENTS: [Vec2; 8] = [
+    vec2(1., 0.),
+    vec2(-1., 0.),
+    vec2(0., 1.),
+    vec2(0., -1.),
+    vec2(D, D),
+    vec2(-D, D),
+    vec2(D, -D),
+    vec2(-D, -D),
+];
+
+/// Derive an unrelated seed, for octaves and channels.
+pub fn sub_seed(seed: u32, i: u32) -> u32 {
+    hash(seed, i)
+}
+
+fn hash(a: u32, b: u32) -> u32 {
+    // murmur3 finalizer
+    let mut h = a ^ b.wrapping_mul(0x9e37_79b9);
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2_ae35);
+    h ^= h >> 16;
+    h
+}
+
+fn gradient(i: i32, j: i32, seed: u32) -> Vec2 {
+    GRADIENTS[(hash(hash(seed, i as u32), j as u32) & 7) as usize]
+}
+
+/// 2d simplex noise in about `-1..1`.
+/// The gradients come from hashing the lattice points, so no permutation table is needed.
+pub fn simplex(p: Vec2, seed: u32) -> f32 {
+    // skew factors between the simplex grid and the square grid,
+    // (sqrt(3) - 1) / 2 and (3 - sqrt(3)) / 6
+    const F2: f32 = 0.366_025_42;
+    const G2: f32 = 0.211_324_87;
+    // scales the result to about -1..1 for unit length gradients
+    const SCALE: f32 = 99.204_33;
+    let s = (p.x + p.y) * F2;
+    let i = (p.x + s).floor();
+    let j = (p.y + s).floor();
+    let t = (i + j) * G2;
+    let p0 = p - vec2(i - t, j - t);
+    let corner = if p0.x > p0.y {
+        vec2(1., 0.)
+    } else {
+        vec2(0., 1.)
+    };
+    let p1 = p0 - corner + Vec2::splat(G2);
+    let p2 = p0 - Vec2::ONE + Vec2::splat(2. * G2);
+    let (i, j) = (i as i32, j as i32);
+    let contribution = |d: Vec2, i: i32, j: i32| {
+        let t = 0.5 - d.length_squared();
+        if t < 0. {
+            0.
+        } else {
+            t.powi(4) * gradient(i, j, seed).dot(d)
+        }
+    };
+    SCALE
+        * (contribution(p0, i, j)
+            + contribution(p1, i + corner.x as i32, j + corner.y as i32)
+            + contribution(p2, i + 1, j + 1))
+}
+
+/// Fractal brownian motion, normalized to about `-1..1`.
+pub fn fbm(p: Vec2, seed: u32, params: &Params) -> f32 {
+    let mut sum = 0.;
+    let mut total_amplitude = 0.;
+    let mut amplitude = 1.;
+    let mut frequency = 1.;
+    for octave in 0..params.octaves.max(1) {
+        sum += amplitude * simplex(p * frequency, sub_seed(seed, octave));
+        total_amplitude += amplitude;
+        amplitude *= params.gain;
+        frequency *= params.lacunarity;
+    }
+    sum / total_amplitude
+}
+
+/// Noise of the kind given by `params`, in about `-1..1`.
+pub fn noise(p: Vec2, seed: u32, params: &Params) -> f32 {
+    match params.kind {
+        Kind::Simplex => simplex(p, seed),
+        Kind::Fbm => fbm(p, seed, params),
+        Kind::DomainWarp => {
+            let offset = vec2(
+                fbm(p, sub_seed(seed, 1000), params),
+                fbm(p, sub_seed(seed, 1001), params),
+            );
+            fbm(p + offset * params.warp, seed, params)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deterministic_and_bounded() {
+        let params = Params {
+            kind: Kind::DomainWarp,
+            octaves: 4,
+            lacunarity: 2.,
+            gain: 0.5,
+            warp: 1.,
+        };
+        let values: Vec<f32> = (0..1000)
+            .map(|i| noise(vec2(i as f32 * 0.37, i as f32 * 0.11), 42, &params))
+            .collect();
+        let again: Vec<f32> = (0..1000)
+            .map(|i| noise(vec2(i as f32 * 0.37, i as f32 * 0.11), 42, &params))
+            .collect();
+        assert_eq!(values, again);
+        assert!(values.iter().all(|v| v.abs() <= 1.));
+        let other_seed = noise(vec2(0.37, 0.11), 43, &params);
+        assert_ne!(values[1], other_seed);
+    }
+}
@@ -0,0 +1,155 @@
+use crate::{
+    designer,
+    lab_ui::LabUi,
+    noise::{self, Kind, Params},
+    utils::{oklab_to_srgb, oklab_to_srgb_clipped, render_par, resettable_slider, NEUTRAL_LAB},
+};
+use eframe::{egui, emath::Rect};
+use glam::vec2;
+use palette::{Oklab, Srgb};
+use strum::IntoEnumIterator;
+
+/// Independent noise fields for L, a and b around a center color.
+#[derive(PartialEq, Clone)]
+pub struct Gradient {
+    seed: u32,
+    params: Params,
+    /// noise features per unit of the design
+    frequency: f32,
+    center: Oklab,
+    /// how far each axis moves away from the center
+    amplitude: Oklab,
+    extend: bool,
+}
+
+impl Gradient {
+    const SEED_DEFAULT: u32 = 0;
+    const PARAMS_DEFAULT: Params = Params {
+        kind: Kind::Fbm,
+        octaves: 4,
+        lacunarity: 2.,
+        gain: 0.5,
+        warp: 1.,
+    };
+    const FREQUENCY_DEFAULT: f32 = 3.;
+    const CENTER_DEFAULT: Oklab = NEUTRAL_LAB;
+    const AMPLITUDE_DEFAULT: Oklab = Oklab {
+        l: 0.3,
+        a: 0.1,
+        b: 0.1,
+    };
+
+    pub fn new() -> Self {
+        Self {
+            seed: Self::SEED_DEFAULT,
+            params: Self::PARAMS_DEFAULT,
+            frequency: Self::FREQUENCY_DEFAULT,
+            center: Self::CENTER_DEFAULT,
+            amplitude: Self::AMPLITUDE_DEFAULT,
+            extend: true,
+        }
+    }
+}
+
+impl designer::Designer for Gradient {
+    fn show_ui(&mut self, ui: &mut eframe::egui::Ui) -> bool {
+        let mut c = self.clone();
+        let Gradient {
+            seed,
+            params,
+            frequency,
+            center,
+            amplitude,
+            extend,
+        } = &mut c;
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                ui.label("seed");
+                ui.add(egui::DragValue::new(seed));
+                if ui.button("🎲").on_hover_text("next seed").clicked() {
+                    *seed = seed.wrapping_add(1);
+                }
+            });
+            ui.horizontal(|ui| {
+                for kind in Kind::iter() {
+                    ui.selectable_value(&mut params.kind, kind, format!("{:?}", kind));
+                }
+            });
+            resettable_slider(
+                ui,
+                frequency,
+                "frequency",
+                0.1..=20.,
+                Self::FREQUENCY_DEFAULT,
+            );
+            ui.add_enabled_ui(params.kind != Kind::Simplex, |ui| {
+                resettable_slider(
+                    ui,
+                    &mut params.octaves,
+                    "octaves",
+                    1..=8,
+                    Self::PARAMS_DEFAULT.octaves,
+                );
+                resettable_slider(
+                    ui,
+                    &mut params.lacunarity,
+                    "lacunarity",
+                    1. ..=4.,
+                    Self::PARAMS_DEFAULT.lacunarity,
+                );
+                resettable_slider(
+                    ui,
+                    &mut params.gain,
+                    "gain",
+                    0. ..=1.,
+                    Self::PARAMS_DEFAULT.gain,
+                );
+            });
+            ui.add_enabled_ui(params.kind == Kind::DomainWarp, |ui| {
+                resettable_slider(
+                    ui,
+                    &mut params.warp,
+                    "warp",
+                    0. ..=4.,
+                    Self::PARAMS_DEFAULT.warp,
+                );
+            });
+            ui.add(LabUi::new(center, "center").default_value(Self::CENTER_DEFAULT));
+            ui.add(
+                LabUi::new(amplitude, "amplitude")
+                    .default_value(Self::AMPLITUDE_DEFAULT)
+                    .a_range(0.0..=0.4)
+                    .b_range(0.0..=0.4)
+                    .picker(false),
+            );
+            ui.checkbox(extend, "extend");
+        });
+        if c != *self {
+            *self = c;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn sample(&self, x: f32, y: f32) -> Oklab {
+        let p = vec2(x, y) * self.frequency;
+        let channel = |i| noise::noise(p, noise::sub_seed(self.seed, i), &self.params);
+        Oklab::new(
+            self.center.l + channel(0) * self.amplitude.l,
+            self.center.a + channel(1) * self.amplitude.a,
+            self.center.b + channel(2) * self.amplitude.b,
+        )
+    }
+
+    fn render(&self, size: (usize, usize), region: Rect, buf: &mut [Srgb]) {
+        render_par(size, region, buf, |x, y| {
+            let lab = self.sample(x, y);
+            if self.extend {
+                oklab_to_srgb_clipped(lab)
+            } else {
+                oklab_to_srgb(&lab)
+            }
+        });
+    }
+}
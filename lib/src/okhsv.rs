@@ -0,0 +1,255 @@
+#![allow(clippy::excessive_precision)]
+
+// converted to rust from https://bottosson.github.io/posts/colorpicker/
+
+// Copyright (c) 2021 Björn Ottosson
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy of
+// this software and associated documentation files (the "Software"), to deal in
+// the Software without restriction, including without limitation the rights to
+// use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+// of the Software, and to permit persons to whom the Software is furnished to do
+// so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// Okhsv and Okhsl, using the gamut approximations from `gamut_mapping`.
+// Hues are in 0..1 turns, saturation, value and lightness in 0..1.
+
+use palette::Oklab;
+
+use crate::gamut_mapping::{find_cusp, find_gamut_intersection, oklab_to_linear_srgb, OKLab, LC};
+
+const K1: f32 = 0.206;
+const K2: f32 = 0.03;
+const K3: f32 = (1. + K1) / (1. + K2);
+
+/// Lightness estimate that is closer to CIELAB L*, used as l and v.
+pub fn toe(x: f32) -> f32 {
+    0.5 * (K3 * x - K1 + ((K3 * x - K1) * (K3 * x - K1) + 4. * K2 * K3 * x).sqrt())
+}
+
+pub fn toe_inv(x: f32) -> f32 {
+    (x * x + K1 * x) / (K3 * (x + K2))
+}
+
+/// Saturation S = C/L and T = C/(1-L) of the cusp.
+fn to_st(cusp: LC) -> (f32, f32) {
+    (cusp.c / cusp.l, cusp.c / (1. - cusp.l))
+}
+
+// Returns a smooth approximation of the location of the cusp
+// This polynomial was created by an optimization process
+// It has been designed so that S_mid < S_max and T_mid < T_max
+fn get_st_mid(a: f32, b: f32) -> (f32, f32) {
+    let s = 0.11516993
+        + 1. / (7.44778970
+            + 4.15901240 * b
+            + a * (-2.19557347
+                + 1.75198401 * b
+                + a * (-2.13704948 - 10.02301043 * b
+                    + a * (-4.24894561 + 5.38770819 * b + 4.69891013 * a))));
+    let t = 0.11239642
+        + 1. / (1.61320320 - 0.68124379 * b
+            + a * (0.40370612
+                + 0.90148123 * b
+                + a * (-0.27087943
+                    + 0.61223990 * b
+                    + a * (0.00299215 - 0.45399568 * b - 0.14661872 * a))));
+    (s, t)
+}
+
+/// Chroma at saturation 0, 0.8 and 1 of Okhsl.
+fn get_cs(l: f32, a: f32, b: f32) -> (f32, f32, f32) {
+    let cusp = find_cusp(a, b);
+    let c_max = find_gamut_intersection(a, b, l, 1., l);
+    let (s_max, t_max) = to_st(cusp);
+    // Scale factor to compensate for the curved part of gamut shape
+    let k = c_max / (l * s_max).min((1. - l) * t_max);
+    let c_mid = {
+        let (s_mid, t_mid) = get_st_mid(a, b);
+        // Use a soft minimum function, instead of a sharp triangle shape to get a smooth value for chroma.
+        let c_a = l * s_mid;
+        let c_b = (1. - l) * t_mid;
+        0.9 * k * (1. / (1. / c_a.powi(4) + 1. / c_b.powi(4))).sqrt().sqrt()
+    };
+    let c_0 = {
+        // for C_0, the shape is independent of hue, so ST are constant.
+        // Values picked to roughly be the average values of ST.
+        let c_a = l * 0.4;
+        let c_b = (1. - l) * 0.8;
+        // Use a soft minimum function, instead of a sharp triangle shape to get a smooth value for chroma.
+        (1. / (1. / (c_a * c_a) + 1. / (c_b * c_b))).sqrt()
+    };
+    (c_0, c_mid, c_max)
+}
+
+/// Normalized hue direction, or an arbitrary one for grays.
+fn hue_direction(lab: Oklab) -> (f32, f32, f32) {
+    let c = lab.a.hypot(lab.b);
+    if c < 1e-6 {
+        (1., 0., 0.)
+    } else {
+        (lab.a / c, lab.b / c, c)
+    }
+}
+
+fn hue_to_turns(a: f32, b: f32) -> f32 {
+    0.5 + 0.5 * (-b).atan2(-a) / std::f32::consts::PI
+}
+
+pub fn okhsl_to_oklab(h: f32, s: f32, l: f32) -> Oklab {
+    if l >= 1. {
+        return Oklab::new(1., 0., 0.);
+    } else if l <= 0. {
+        return Oklab::new(0., 0., 0.);
+    }
+    let (b, a) = (std::f32::consts::TAU * h).sin_cos();
+    let l = toe_inv(l);
+    let (c_0, c_mid, c_max) = get_cs(l, a, b);
+    // Interpolate the three values for C so that:
+    // At s=0: dC/ds = C_0, C=0
+    // At s=0.8: C=C_mid
+    // At s=1.0: C=C_max
+    const MID: f32 = 0.8;
+    const MID_INV: f32 = 1.25;
+    let c = if s < MID {
+        let t = MID_INV * s;
+        let k_1 = MID * c_0;
+        let k_2 = 1. - k_1 / c_mid;
+        t * k_1 / (1. - k_2 * t)
+    } else {
+        let t = (s - MID) / (1. - MID);
+        let k_0 = c_mid;
+        let k_1 = (1. - MID) * c_mid * c_mid * MID_INV * MID_INV / c_0;
+        let k_2 = 1. - k_1 / (c_max - c_mid);
+        k_0 + t * k_1 / (1. - k_2 * t)
+    };
+    Oklab::new(l, c * a, c * b)
+}
+
+/// `(h, s, l)` of a color, which should be in gamut.
+pub fn oklab_to_okhsl(lab: Oklab) -> (f32, f32, f32) {
+    let (a, b, c) = hue_direction(lab);
+    let h = hue_to_turns(a, b);
+    let l = lab.l;
+    if l >= 1. || l <= 0. || c < 1e-6 {
+        return (h, 0., toe(l.clamp(0., 1.)));
+    }
+    let (c_0, c_mid, c_max) = get_cs(l, a, b);
+    const MID: f32 = 0.8;
+    const MID_INV: f32 = 1.25;
+    let s = if c < c_mid {
+        let k_1 = MID * c_0;
+        let k_2 = 1. - k_1 / c_mid;
+        let t = c / (k_1 + k_2 * c);
+        t * MID
+    } else {
+        let k_0 = c_mid;
+        let k_1 = (1. - MID) * c_mid * c_mid * MID_INV * MID_INV / c_0;
+        let k_2 = 1. - k_1 / (c_max - c_mid);
+        let t = (c - k_0) / (k_1 + k_2 * (c - k_0));
+        MID + (1. - MID) * t
+    };
+    (h, s, toe(l))
+}
+
+/// Largest component of the linear sRGB color, used to scale colors onto the gamut boundary.
+fn max_rgb(lab: OKLab) -> f32 {
+    let rgb = oklab_to_linear_srgb(lab);
+    rgb.r.max(rgb.g).max(rgb.b).max(0.)
+}
+
+pub fn okhsv_to_oklab(h: f32, s: f32, v: f32) -> Oklab {
+    if v <= 0. {
+        return Oklab::new(0., 0., 0.);
+    }
+    let (b, a) = (std::f32::consts::TAU * h).sin_cos();
+    let (s_max, t_max) = to_st(find_cusp(a, b));
+    const S_0: f32 = 0.5;
+    let k = 1. - S_0 / s_max;
+    // first we compute L and V as if the gamut is a perfect triangle:
+    // L, C when v==1:
+    let l_v = 1. - s * S_0 / (S_0 + t_max - t_max * k * s);
+    let c_v = s * t_max * S_0 / (S_0 + t_max - t_max * k * s);
+    let mut l = v * l_v;
+    let mut c = v * c_v;
+    // then we compensate for both toe and the curved top part of the triangle:
+    let l_vt = toe_inv(l_v);
+    let c_vt = c_v * l_vt / l_v;
+    let l_new = toe_inv(l);
+    c *= l_new / l;
+    l = l_new;
+    let scale_l = (1.
+        / max_rgb(OKLab {
+            l: l_vt,
+            a: a * c_vt,
+            b: b * c_vt,
+        }))
+    .cbrt();
+    l *= scale_l;
+    c *= scale_l;
+    Oklab::new(l, c * a, c * b)
+}
+
+/// `(h, s, v)` of a color, which should be in gamut.
+pub fn oklab_to_okhsv(lab: Oklab) -> (f32, f32, f32) {
+    let (a, b, c) = hue_direction(lab);
+    let h = hue_to_turns(a, b);
+    if lab.l <= 0. {
+        return (h, 0., 0.);
+    }
+    let (s_max, t_max) = to_st(find_cusp(a, b));
+    const S_0: f32 = 0.5;
+    let k = 1. - S_0 / s_max;
+    // first we find L_v, C_v, L_vt and C_vt
+    let t = t_max / (c + lab.l * t_max);
+    let l_v = t * lab.l;
+    let c_v = t * c;
+    let l_vt = toe_inv(l_v);
+    let c_vt = c_v * l_vt / l_v;
+    // we can then use these to invert the step that compensates for the toe and the curved top part of the triangle:
+    let scale_l = (1.
+        / max_rgb(OKLab {
+            l: l_vt,
+            a: a * c_vt,
+            b: b * c_vt,
+        }))
+    .cbrt();
+    let l = toe(lab.l / scale_l);
+    // we can now compute v and s:
+    let v = l / l_v;
+    let s = (S_0 + t_max) * c_v / ((t_max * S_0) + t_max * k * c_v);
+    (h, s, v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips() {
+        for (h, s, x) in [
+            (0.1, 0.5, 0.5),
+            (0.6, 0.9, 0.3),
+            (0.85, 0.2, 0.9),
+            (0.3, 1., 0.7),
+        ] {
+            let (h2, s2, v2) = oklab_to_okhsv(okhsv_to_oklab(h, s, x));
+            assert!((h - h2).abs() < 1e-3 && (s - s2).abs() < 1e-3 && (x - v2).abs() < 1e-3);
+            let (h2, s2, l2) = oklab_to_okhsl(okhsl_to_oklab(h, s, x));
+            assert!((h - h2).abs() < 1e-3 && (s - s2).abs() < 1e-3 && (x - l2).abs() < 1e-3);
+        }
+        // full saturation and value is on the gamut boundary
+        assert!(crate::utils::is_in_gamut(okhsv_to_oklab(0.3, 1., 1.)));
+    }
+}
@@ -0,0 +1,152 @@
+use std::ops::RangeInclusive;
+
+use eframe::{
+    egui::{self, Sense, TextStyle, Widget},
+    emath::{pos2, remap, vec2, Align2, Pos2},
+    epaint::{Color32, Shape, Stroke},
+};
+
+/// Simple line plot of evenly spaced samples.
+pub struct LinePlot<'a> {
+    label: &'a str,
+    series: Vec<(&'a [f32], Color32)>,
+    y_range: Option<RangeInclusive<f32>>,
+    periodic: bool,
+    height: f32,
+}
+
+impl<'a> LinePlot<'a> {
+    const HEIGHT_DEFAULT: f32 = 80.;
+
+    pub fn new(label: &'a str) -> Self {
+        Self {
+            label,
+            series: vec![],
+            y_range: None,
+            periodic: false,
+            height: Self::HEIGHT_DEFAULT,
+        }
+    }
+
+    pub fn series(mut self, values: &'a [f32], color: Color32) -> Self {
+        self.series.push((values, color));
+        self
+    }
+
+    /// Use a fixed range instead of fitting the range to the data.
+    pub fn y_range(mut self, range: RangeInclusive<f32>) -> Self {
+        self.y_range = Some(range);
+        self
+    }
+
+    /// The values wrap around at the ends of the y range, like hue angles do.
+    pub fn periodic(mut self) -> Self {
+        self.periodic = true;
+        self
+    }
+
+    pub fn height(mut self, height: f32) -> Self {
+        self.height = height;
+        self
+    }
+
+    fn fitted_range(&self) -> RangeInclusive<f32> {
+        if let Some(range) = &self.y_range {
+            return range.clone();
+        }
+        let (min, max) = self
+            .series
+            .iter()
+            .flat_map(|(values, _)| values.iter())
+            .filter(|v| v.is_finite())
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), &v| {
+                (min.min(v), max.max(v))
+            });
+        if min > max {
+            0. ..=1.
+        } else if max - min < 1e-6 {
+            min - 0.5e-3..=max + 0.5e-3
+        } else {
+            min..=max
+        }
+    }
+}
+
+impl<'a> Widget for LinePlot<'a> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        let range = self.fitted_range();
+        let (response, painter) =
+            ui.allocate_painter(vec2(ui.available_width(), self.height), Sense::hover());
+        let rect = response.rect;
+        let visuals = ui.visuals();
+        painter.rect(
+            rect,
+            0.,
+            visuals.extreme_bg_color,
+            visuals.widgets.noninteractive.bg_stroke,
+        );
+        let plot_rect = rect.shrink(2.);
+        let to_screen = |i: usize, n: usize, v: f32| -> Pos2 {
+            pos2(
+                plot_rect.left() + plot_rect.width() * i as f32 / (n.max(2) - 1) as f32,
+                remap(v, range.clone(), plot_rect.bottom()..=plot_rect.top()),
+            )
+        };
+        let half_period = (range.end() - range.start()) / 2.;
+        for (values, color) in &self.series {
+            let stroke = Stroke::new(1.5, *color);
+            let mut line = vec![];
+            for (i, &v) in values.iter().enumerate() {
+                let wraps = self.periodic && i > 0 && (v - values[i - 1]).abs() > half_period;
+                if !v.is_finite() || wraps {
+                    painter.add(Shape::line(std::mem::take(&mut line), stroke));
+                }
+                if v.is_finite() {
+                    line.push(to_screen(i, values.len(), v));
+                }
+            }
+            painter.add(Shape::line(line, stroke));
+        }
+        let font = TextStyle::Small.resolve(ui.style());
+        painter.text(
+            rect.left_top() + vec2(4., 2.),
+            Align2::LEFT_TOP,
+            self.label,
+            font.clone(),
+            visuals.text_color(),
+        );
+        painter.text(
+            rect.right_top() + vec2(-4., 2.),
+            Align2::RIGHT_TOP,
+            format!("{:.3}", range.end()),
+            font.clone(),
+            visuals.weak_text_color(),
+        );
+        painter.text(
+            rect.right_bottom() + vec2(-4., -2.),
+            Align2::RIGHT_BOTTOM,
+            format!("{:.3}", range.start()),
+            font,
+            visuals.weak_text_color(),
+        );
+        if let Some(pos) = response.hover_pos() {
+            painter.vline(
+                pos.x,
+                rect.y_range(),
+                visuals.widgets.noninteractive.fg_stroke,
+            );
+            let t = ((pos.x - plot_rect.left()) / plot_rect.width()).clamp(0., 1.);
+            let series = self.series;
+            return response.on_hover_ui_at_pointer(|ui| {
+                for (values, color) in series {
+                    if values.is_empty() {
+                        continue;
+                    }
+                    let i = (t * (values.len() - 1) as f32).round() as usize;
+                    ui.colored_label(color, format!("{:.4}", values[i]));
+                }
+            });
+        }
+        response
+    }
+}
@@ -0,0 +1,176 @@
+use std::cmp::Ordering;
+
+use eframe::{
+    egui::{self, Sense},
+    emath::{self, Pos2},
+    epaint::{Color32, Shape, Stroke},
+};
+use glam::{vec3, Quat, Vec3};
+use palette::{convert::FromColorUnclamped, LinSrgb, Oklab};
+
+use crate::{
+    designer::Designer,
+    rotator::{drag_rotation, project},
+    utils::{oklab_to_srgb_clipped, srgb_to_color32},
+};
+
+/// Position of a color in 3d views of Oklab, before rotation.
+/// Lightness points up, and the gamut fits in the unit sphere.
+pub fn lab_to_view(lab: Oklab) -> Vec3 {
+    const SCALE: f32 = 1.5;
+    vec3(lab.a, lab.l - 0.5, -lab.b) * SCALE
+}
+
+/// 3d view of the colors of the design in Oklab.
+pub struct PointCloud {
+    pub open: bool,
+    rotation: Quat,
+    show_hull: bool,
+    show_path: bool,
+    points: Option<Vec<Oklab>>,
+    path: Option<Vec<Oklab>>,
+}
+
+impl PointCloud {
+    /// look at the a/b plane slightly from above
+    const TILT: f32 = 0.5;
+    /// number of samples along each axis of the design
+    const GRID: usize = 48;
+    /// samples along each edge of the gamut hull
+    const HULL_STEPS: usize = 16;
+
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            rotation: Self::rotation_default(),
+            show_hull: true,
+            show_path: true,
+            points: None,
+            path: None,
+        }
+    }
+
+    fn rotation_default() -> Quat {
+        Quat::from_rotation_x(Self::TILT)
+    }
+
+    pub fn invalidate(&mut self) {
+        self.points = None;
+        self.path = None;
+    }
+
+    pub fn show_window(&mut self, ctx: &egui::Context, designer: &dyn Designer) {
+        let mut open = self.open;
+        egui::Window::new("oklab 3d")
+            .open(&mut open)
+            .default_width(300.)
+            .show(ctx, |ui| self.ui(ui, designer));
+        self.open = open;
+    }
+
+    /// The edges of the sRGB cube, mapped to Oklab.
+    fn hull_edges() -> Vec<Vec<Oklab>> {
+        let corners = (0..8).map(|i| vec3((i & 1) as f32, (i >> 1 & 1) as f32, (i >> 2) as f32));
+        corners
+            .flat_map(|start| {
+                [Vec3::X, Vec3::Y, Vec3::Z]
+                    .into_iter()
+                    .filter(move |&axis| start.dot(axis) == 0.)
+                    .map(move |axis| {
+                        (0..=Self::HULL_STEPS)
+                            .map(|i| {
+                                let v = start + axis * i as f32 / Self::HULL_STEPS as f32;
+                                Oklab::from_color_unclamped(LinSrgb::new(v.x, v.y, v.z))
+                            })
+                            .collect()
+                    })
+            })
+            .collect()
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, designer: &dyn Designer) {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.show_hull, "gamut hull");
+            ui.checkbox(&mut self.show_path, "path")
+                .on_hover_text("the curve through Oklab that the design follows, if it has one");
+            if ui
+                .add_enabled(
+                    self.rotation != Self::rotation_default(),
+                    egui::Button::new("⟲"),
+                )
+                .on_hover_text("reset rotation")
+                .clicked()
+            {
+                self.rotation = Self::rotation_default();
+            }
+        });
+        let points = self.points.get_or_insert_with(|| {
+            let n = Self::GRID;
+            (0..n * n)
+                .map(|i| {
+                    designer.sample(
+                        (i % n) as f32 / (n - 1) as f32,
+                        (i / n) as f32 / (n - 1) as f32,
+                    )
+                })
+                .collect()
+        });
+        let path = self.path.get_or_insert_with(|| designer.path());
+
+        let side = ui.available_width().min(400.);
+        let (response, painter) = ui.allocate_painter(emath::vec2(side, side), Sense::drag());
+        drag_rotation(ui, &response, &mut self.rotation);
+        let rect = response.rect;
+        painter.rect_filled(rect, 0., ui.visuals().extreme_bg_color);
+        let rotation = self.rotation;
+        let to_screen = |lab: Oklab| -> (Pos2, f32) {
+            let (p, depth) = project(rotation * lab_to_view(lab));
+            (rect.center() + p * rect.width() / 2., depth)
+        };
+
+        // everything is painted back to front
+        let mut shapes: Vec<(f32, Shape)> = Vec::new();
+        let mut add_line = |line: &[Oklab], color: Option<Color32>| {
+            for w in line.windows(2) {
+                let (a, depth_a) = to_screen(w[0]);
+                let (b, depth_b) = to_screen(w[1]);
+                let color = color.unwrap_or_else(|| srgb_to_color32(oklab_to_srgb_clipped(w[0])));
+                shapes.push((
+                    (depth_a + depth_b) / 2.,
+                    Shape::line_segment([a, b], Stroke::new(1., color)),
+                ));
+            }
+        };
+        // lightness axis
+        add_line(
+            &[Oklab::new(0., 0., 0.), Oklab::new(1., 0., 0.)],
+            Some(ui.visuals().weak_text_color()),
+        );
+        if self.show_hull {
+            for edge in Self::hull_edges() {
+                add_line(&edge, None);
+            }
+        }
+        if self.show_path {
+            add_line(path, Some(ui.visuals().text_color()));
+        }
+        for &lab in points.iter() {
+            let (pos, depth) = to_screen(lab);
+            shapes.push((
+                depth,
+                Shape::circle_filled(
+                    pos,
+                    1.5 + depth * 2.,
+                    srgb_to_color32(oklab_to_srgb_clipped(lab)),
+                ),
+            ));
+        }
+        shapes.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        painter
+            .with_clip_rect(rect)
+            .extend(shapes.into_iter().map(|(_, shape)| shape));
+        if path.is_empty() && self.show_path {
+            ui.weak("this designer has no path");
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use crate::{
+    designer,
+    utils::{
+        oklab_to_srgb, oklab_to_srgb_clipped, range_sliders, render_par, resettable_slider,
+        smooth_par, NEUTRAL_LAB,
+    },
+};
+use eframe::emath::{pos2, Pos2, Rect};
+use glam::vec2;
+use palette::{Oklab, Srgb};
+use std::f32::consts::{PI, TAU};
+
+#[derive(PartialEq, Clone, Copy)]
+enum Mode {
+    /// distance from the center drives the color
+    Radial,
+    /// angle around the center drives the color
+    Conic,
+}
+
+/// Oklch gradient in polar coordinates around a center.
+/// The main coordinate (radius or angle) goes through the L, C and h ranges,
+/// the other one twists the hue.
+#[derive(PartialEq, Clone)]
+pub struct Gradient {
+    mode: Mode,
+    center: Pos2,
+    /// distance from the center where the end of the ranges is reached
+    radius: f32,
+    lightness: (f32, f32),
+    chroma: (f32, f32),
+    hue: (f32, f32),
+    twist: f32,
+    saturation_non_midtone: f32,
+    extend: bool,
+    smooth: f32,
+}
+
+impl Gradient {
+    const CENTER_DEFAULT: Pos2 = pos2(0.5, 0.5);
+    const RADIUS_DEFAULT: f32 = 0.5;
+    const TWIST_DEFAULT: f32 = 0.;
+    const SATURATION_NON_MIDTONE_DEFAULT: f32 = 1.;
+    const SMOOTH_DEFAULT: f32 = 0.;
+    const RADIAL_LIGHTNESS_DEFAULT: (f32, f32) = (0.95, 0.35);
+    const RADIAL_CHROMA_DEFAULT: (f32, f32) = (0., 0.15);
+    const RADIAL_HUE_DEFAULT: (f32, f32) = (1., 4.);
+    const CONIC_LIGHTNESS_DEFAULT: (f32, f32) = (0.7, 0.7);
+    const CONIC_CHROMA_DEFAULT: (f32, f32) = (0.12, 0.12);
+    const CONIC_HUE_DEFAULT: (f32, f32) = (0., TAU);
+
+    pub fn radial() -> Self {
+        Self {
+            mode: Mode::Radial,
+            center: Self::CENTER_DEFAULT,
+            radius: Self::RADIUS_DEFAULT,
+            lightness: Self::RADIAL_LIGHTNESS_DEFAULT,
+            chroma: Self::RADIAL_CHROMA_DEFAULT,
+            hue: Self::RADIAL_HUE_DEFAULT,
+            twist: Self::TWIST_DEFAULT,
+            saturation_non_midtone: Self::SATURATION_NON_MIDTONE_DEFAULT,
+            extend: true,
+            smooth: Self::SMOOTH_DEFAULT,
+        }
+    }
+
+    pub fn conic() -> Self {
+        Self {
+            mode: Mode::Conic,
+            lightness: Self::CONIC_LIGHTNESS_DEFAULT,
+            chroma: Self::CONIC_CHROMA_DEFAULT,
+            hue: Self::CONIC_HUE_DEFAULT,
+            ..Self::radial()
+        }
+    }
+
+    fn defaults(&self) -> Self {
+        match self.mode {
+            Mode::Radial => Self::radial(),
+            Mode::Conic => Self::conic(),
+        }
+    }
+}
+
+impl designer::Designer for Gradient {
+    fn show_ui(&mut self, ui: &mut eframe::egui::Ui) -> bool {
+        let defaults = self.defaults();
+        let mut c = self.clone();
+        let Gradient {
+            mode,
+            center,
+            radius,
+            lightness,
+            chroma,
+            hue,
+            twist,
+            saturation_non_midtone,
+            extend,
+            smooth,
+        } = &mut c;
+        ui.vertical(|ui| {
+            resettable_slider(ui, &mut center.x, "center x", 0. ..=1., defaults.center.x);
+            resettable_slider(ui, &mut center.y, "center y", 0. ..=1., defaults.center.y);
+            if *mode == Mode::Radial {
+                resettable_slider(ui, radius, "radius", 0.01..=1.5, defaults.radius);
+            }
+            range_sliders(
+                ui,
+                lightness,
+                ["L start", "L end"],
+                0. ..=1.,
+                defaults.lightness,
+            );
+            range_sliders(ui, chroma, ["C start", "C end"], 0. ..=0.4, defaults.chroma);
+            range_sliders(
+                ui,
+                hue,
+                ["hue start", "hue end"],
+                -TAU..=TAU * 2.,
+                defaults.hue,
+            );
+            resettable_slider(ui, twist, "twist", -PI * 5.0..=PI * 5., defaults.twist);
+            resettable_slider(
+                ui,
+                saturation_non_midtone,
+                "saturation !midtone",
+                0. ..=1.,
+                defaults.saturation_non_midtone,
+            );
+            ui.checkbox(extend, "extend");
+            ui.add_enabled_ui(*extend, |ui| {
+                resettable_slider(ui, smooth, "smooth", 0. ..=100., defaults.smooth);
+            });
+        });
+        if c != *self {
+            *self = c;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn sample(&self, x: f32, y: f32) -> Oklab {
+        let d = vec2(x - self.center.x, y - self.center.y);
+        let distance = (d.length() / self.radius).min(1.);
+        let angle = (d.y.atan2(d.x) / TAU).rem_euclid(1.);
+        let (t, twist_t) = match self.mode {
+            // the twist has to be periodic in the angle, or there is a seam along +x
+            Mode::Radial => (distance, (angle * TAU).sin()),
+            Mode::Conic => (angle, distance),
+        };
+        let lerp = |(start, end): (f32, f32)| start + (end - start) * t;
+        let lightness = lerp(self.lightness);
+        let hue = lerp(self.hue) + twist_t * self.twist;
+        let midtone_mask = ((lightness - NEUTRAL_LAB.l).abs() * 2.).powi(2);
+        let chroma =
+            (lerp(self.chroma) * (1. - (1. - self.saturation_non_midtone) * midtone_mask)).max(0.);
+        Oklab::new(lightness, chroma * hue.cos(), chroma * hue.sin())
+    }
+
+    fn render(&self, size: (usize, usize), region: Rect, buf: &mut [Srgb]) {
+        render_par(size, region, buf, |x, y| {
+            let lab = self.sample(x, y);
+            if self.extend {
+                oklab_to_srgb_clipped(lab)
+            } else {
+                oklab_to_srgb(&lab)
+            }
+        });
+        if self.smooth > 0. && self.extend {
+            smooth_par(size, region, buf, self.smooth);
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use eframe::{
+    egui::{self, Painter},
+    emath::Pos2,
+    epaint::{Color32, Shape, Stroke},
+};
+use palette::{convert::FromColorUnclamped, Oklab, Oklch};
+
+use crate::{
+    canvas::Canvas,
+    designer::Designer,
+    plot::LinePlot,
+    utils::{delta_e_ok, oklab_clipped},
+};
+
+const UNCLIPPED_COLOR: Color32 = Color32::GRAY;
+const MAPPED_COLOR: Color32 = Color32::from_rgb(255, 140, 60);
+
+/// Lightness, chroma, hue and cumulative ΔEOK along a path of colors.
+struct Profile {
+    l: Vec<f32>,
+    c: Vec<f32>,
+    h: Vec<f32>,
+    delta_e: Vec<f32>,
+}
+
+impl Profile {
+    fn new(labs: &[Oklab]) -> Self {
+        let lchs: Vec<_> = labs
+            .iter()
+            .map(|&lab| Oklch::from_color_unclamped(lab))
+            .collect();
+        let mut acc = 0.;
+        let delta_e = labs
+            .iter()
+            .enumerate()
+            .map(|(i, &lab)| {
+                if i > 0 {
+                    acc += delta_e_ok(labs[i - 1], lab);
+                }
+                acc
+            })
+            .collect();
+        Self {
+            l: lchs.iter().map(|c| c.l).collect(),
+            c: lchs.iter().map(|c| c.chroma).collect(),
+            h: lchs.iter().map(|c| c.hue.to_positive_degrees()).collect(),
+            delta_e,
+        }
+    }
+}
+
+/// A polyline drawn on the preview, along which the design is plotted.
+pub struct Probe {
+    points: Vec<Pos2>,
+    /// position along the probe that is hovered in the plots
+    hovered: Option<f32>,
+    pub open: bool,
+}
+
+impl Probe {
+    const SAMPLES: usize = 256;
+
+    pub fn new() -> Self {
+        Self {
+            points: vec![],
+            hovered: None,
+            open: false,
+        }
+    }
+
+    /// The point at `t` along the probe, where `t` is normalized by the total length.
+    fn point_along(&self, t: f32) -> Pos2 {
+        debug_assert!(self.points.len() >= 2);
+        let lengths: Vec<f32> = self
+            .points
+            .windows(2)
+            .scan(0., |acc, w| {
+                *acc += w[0].distance(w[1]);
+                Some(*acc)
+            })
+            .collect();
+        let d = t * lengths.last().unwrap();
+        let segment = lengths.partition_point(|&l| l < d).min(lengths.len() - 1);
+        let start = if segment == 0 {
+            0.
+        } else {
+            lengths[segment - 1]
+        };
+        let segment_length = lengths[segment] - start;
+        let f = if segment_length > 0. {
+            (d - start) / segment_length
+        } else {
+            0.
+        };
+        self.points[segment].lerp(self.points[segment + 1], f)
+    }
+
+    /// Handle clicks on the canvas and draw the probe on top of it.
+    pub fn show_on_canvas(
+        &mut self,
+        canvas: &Canvas,
+        response: &egui::Response,
+        painter: &Painter,
+        editing: bool,
+    ) {
+        if editing {
+            if response.clicked() {
+                if let Some(pos) = canvas.hover_pos(response) {
+                    self.points.push(pos);
+                    self.open = true;
+                }
+            }
+            if response.secondary_clicked() {
+                self.points.pop();
+            }
+        }
+        for view in canvas.view_rects(response) {
+            let painter = painter.with_clip_rect(view);
+            let screen_points: Vec<_> = self
+                .points
+                .iter()
+                .map(|&p| canvas.to_screen(view, p))
+                .collect();
+            if editing && !response.dragged() {
+                if let (Some(&last), Some(hover_pos)) = (screen_points.last(), response.hover_pos())
+                {
+                    if view.contains(hover_pos) {
+                        painter.line_segment([last, hover_pos], Stroke::new(1., Color32::GRAY));
+                    }
+                }
+            }
+            painter.add(Shape::line(
+                screen_points.clone(),
+                Stroke::new(3., Color32::BLACK),
+            ));
+            painter.add(Shape::line(
+                screen_points.clone(),
+                Stroke::new(1.5, Color32::WHITE),
+            ));
+            for p in screen_points {
+                painter.circle(p, 3., Color32::WHITE, Stroke::new(1., Color32::BLACK));
+            }
+            if let (Some(t), true) = (self.hovered, self.points.len() >= 2) {
+                painter.circle_stroke(
+                    canvas.to_screen(view, self.point_along(t)),
+                    5.,
+                    Stroke::new(2., MAPPED_COLOR),
+                );
+            }
+        }
+    }
+
+    pub fn show_window(&mut self, ctx: &egui::Context, designer: &dyn Designer) {
+        let mut open = self.open;
+        egui::Window::new("probe profile")
+            .open(&mut open)
+            .default_width(300.)
+            .show(ctx, |ui| self.ui(ui, designer));
+        self.open = open;
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, designer: &dyn Designer) {
+        self.hovered = None;
+        ui.horizontal(|ui| {
+            ui.colored_label(UNCLIPPED_COLOR, "unclipped");
+            ui.colored_label(MAPPED_COLOR, "gamut mapped");
+        });
+        if self.points.len() < 2 {
+            ui.label("click on the preview using the probe tool to add points");
+        } else {
+            let labs: Vec<_> = (0..Self::SAMPLES)
+                .map(|i| {
+                    let p = self.point_along(i as f32 / (Self::SAMPLES - 1) as f32);
+                    designer.sample(p.x, p.y)
+                })
+                .collect();
+            let unclipped = Profile::new(&labs);
+            let mapped = Profile::new(
+                &labs
+                    .iter()
+                    .map(|&lab| oklab_clipped(lab))
+                    .collect::<Vec<_>>(),
+            );
+            let responses = [
+                ui.add(
+                    LinePlot::new("L")
+                        .series(&unclipped.l, UNCLIPPED_COLOR)
+                        .series(&mapped.l, MAPPED_COLOR),
+                ),
+                ui.add(
+                    LinePlot::new("C")
+                        .series(&unclipped.c, UNCLIPPED_COLOR)
+                        .series(&mapped.c, MAPPED_COLOR),
+                ),
+                ui.add(
+                    LinePlot::new("h")
+                        .y_range(0. ..=360.)
+                        .periodic()
+                        .series(&unclipped.h, UNCLIPPED_COLOR)
+                        .series(&mapped.h, MAPPED_COLOR),
+                ),
+                ui.add(
+                    LinePlot::new("cumulative ΔEOK")
+                        .series(&unclipped.delta_e, UNCLIPPED_COLOR)
+                        .series(&mapped.delta_e, MAPPED_COLOR),
+                ),
+            ];
+            self.hovered = responses.iter().find_map(|r| {
+                r.hover_pos()
+                    .map(|p| ((p.x - r.rect.left()) / r.rect.width()).clamp(0., 1.))
+            });
+        }
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(!self.points.is_empty(), egui::Button::new("clear"))
+                .clicked()
+            {
+                self.points.clear();
+            }
+            ui.weak("right click removes the last point");
+        });
+    }
+}
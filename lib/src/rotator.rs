@@ -1,7 +1,7 @@
 use std::cmp::Ordering;
 
 use eframe::{
-    egui::{Sense, Widget},
+    egui::{Response, Sense, Ui, Widget},
     emath,
     epaint::{Color32, Stroke},
 };
@@ -25,23 +25,7 @@ impl<'a> Widget for Rotator<'a> {
         const SIZE: f32 = 100.0;
         let (response, painter) =
             ui.allocate_painter(emath::vec2(SIZE, SIZE), Sense::click_and_drag());
-        let id = response.id;
-        // TODO is this correct?
-        let on = if let Some(pos) = response.interact_pointer_pos() {
-            let data = ui.data_mut(|d| {
-                *d.get_temp_mut_or_insert_with(id, || State {
-                    start_rot: *self.quat,
-                    start_pos: pos,
-                })
-            });
-            let d = pos - data.start_pos;
-            // 2d cross product, with flipped y
-            *self.quat = Quat::from_scaled_axis(glam::vec3(d.y, d.x, 0.) * 0.01) * data.start_rot;
-            true
-        } else {
-            ui.data_mut(|d| d.remove::<State>(id));
-            false
-        };
+        let on = drag_rotation(ui, &response, self.quat);
         if ui.is_rect_visible(response.rect) {
             let visuals = ui.style().interact_selectable(&response, on);
             // TODO use some constant?
@@ -57,22 +41,15 @@ impl<'a> Widget for Rotator<'a> {
                 .map(|(v, h)| (v, h, *self.quat * v))
                 .collect::<Vec<_>>();
             axis.sort_by(|(_, _, a), (_, _, b)| a.z.partial_cmp(&b.z).unwrap_or(Ordering::Equal));
-            let camera = glam::Mat4::look_at_rh(vec3(0.0, 0.0, 2.0), Vec3::ZERO, Vec3::Y);
-            let proj = glam::Mat4::perspective_rh(std::f32::consts::FRAC_PI_3, 1.0, 1.0, 3.0);
-            let view_proj = proj * camera;
             for (_, h, rv) in axis {
-                let projected = view_proj.project_point3(rv);
-                let depth = 1.0 - projected.z;
+                let (projected, depth) = project(rv);
                 let c = palette::Srgb::from_color_unclamped(palette::Oklch {
                     l: 0.4 + depth * 0.4,
                     chroma: 0.3 + depth * 0.2,
                     hue: h.into(),
                 })
                 .into_format();
-                let p = rect.center()
-                    + emath::Vec2::from((projected.xy() * vec2(1.0, -1.0)).to_array())
-                        * rect.width()
-                        / 2.1;
+                let p = rect.center() + projected * rect.width() / 2.1;
                 painter.line_segment(
                     [rect.center(), p],
                     Stroke {
@@ -86,6 +63,40 @@ impl<'a> Widget for Rotator<'a> {
     }
 }
 
+/// Rotate `quat` while `response` is being dragged, returns whether it is.
+pub fn drag_rotation(ui: &Ui, response: &Response, quat: &mut Quat) -> bool {
+    let id = response.id;
+    // TODO is this correct?
+    if let Some(pos) = response.interact_pointer_pos() {
+        let data = ui.data_mut(|d| {
+            *d.get_temp_mut_or_insert_with(id, || State {
+                start_rot: *quat,
+                start_pos: pos,
+            })
+        });
+        let d = pos - data.start_pos;
+        // 2d cross product, with flipped y
+        *quat = Quat::from_scaled_axis(glam::vec3(d.y, d.x, 0.) * 0.01) * data.start_rot;
+        true
+    } else {
+        ui.data_mut(|d| d.remove::<State>(id));
+        false
+    }
+}
+
+/// Perspective projection of a point within the unit sphere.
+/// Returns the screen position in `-1..1` with y pointing down,
+/// and the depth, which goes from 0 at the back to 1 at the front.
+pub fn project(v: Vec3) -> (emath::Vec2, f32) {
+    let camera = glam::Mat4::look_at_rh(vec3(0.0, 0.0, 2.0), Vec3::ZERO, Vec3::Y);
+    let proj = glam::Mat4::perspective_rh(std::f32::consts::FRAC_PI_3, 1.0, 1.0, 3.0);
+    let projected = (proj * camera).project_point3(v);
+    (
+        emath::Vec2::from((projected.xy() * vec2(1.0, -1.0)).to_array()),
+        1.0 - projected.z,
+    )
+}
+
 #[derive(Clone, Copy, Debug)]
 struct State {
     start_rot: Quat,
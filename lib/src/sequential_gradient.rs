@@ -0,0 +1,218 @@
+use crate::{
+    colormap::{Colormap, Monotonicity},
+    designer, gamut_mapping,
+    interpolation::catmull_rom_scalar,
+    utils::{oklab_to_srgb_clipped, range_sliders, render_par, resettable_slider, srgb_to_color32},
+};
+use eframe::{
+    egui::{self, Sense},
+    emath::{pos2, remap, vec2, Pos2, Rect},
+    epaint::{Color32, Shape, Stroke},
+};
+use palette::{Oklab, Srgb};
+use std::f32::consts::PI;
+
+/// Sequential colormap along x with strictly monotonic lightness,
+/// a rotating hue and chroma following an editable curve that is kept inside the gamut.
+#[derive(PartialEq, Clone)]
+pub struct Gradient {
+    lightness: (f32, f32),
+    hue: f32,
+    /// hue change from start to end
+    rotation: f32,
+    /// chroma at evenly spaced points along x, before gamut clamping
+    chroma: Vec<f32>,
+    /// lightness of the gamut mapped colormap
+    monotonicity: Monotonicity,
+}
+
+impl Gradient {
+    const LIGHTNESS_DEFAULT: (f32, f32) = (0.25, 0.95);
+    const HUE_DEFAULT: f32 = 4.9;
+    const ROTATION_DEFAULT: f32 = -3.5;
+    const CHROMA_DEFAULT: [f32; 5] = [0.1, 0.14, 0.16, 0.18, 0.2];
+    const MAX_CHROMA: f32 = 0.4;
+    /// keep a little distance to the gamut boundary, which is only found approximately
+    const GAMUT_MARGIN: f32 = 0.995;
+    const CURVE_HEIGHT: f32 = 80.;
+    const HANDLE_RADIUS: f32 = 4.;
+    /// samples used for checking monotonicity and drawing the curve
+    const STEPS: usize = 256;
+
+    pub fn new() -> Self {
+        let mut gradient = Self {
+            lightness: Self::LIGHTNESS_DEFAULT,
+            hue: Self::HUE_DEFAULT,
+            rotation: Self::ROTATION_DEFAULT,
+            chroma: Self::CHROMA_DEFAULT.to_vec(),
+            monotonicity: Monotonicity::Constant,
+        };
+        gradient.check();
+        gradient
+    }
+
+    /// Check the lightness of the colormap as it is displayed, after gamut mapping.
+    fn check(&mut self) {
+        self.monotonicity =
+            Colormap::from_designer(self, 0.5, Self::STEPS).lightness_monotonicity();
+    }
+
+    /// Chroma curve at `t`, before gamut clamping.
+    fn curve(&self, t: f32) -> f32 {
+        let n = self.chroma.len();
+        let f = t.clamp(0., 1.) * (n - 1) as f32;
+        let i = (f as usize).min(n - 2);
+        let at = |j: usize| self.chroma[j.min(n - 1)];
+        let p = [at(i.saturating_sub(1)), at(i), at(i + 1), at(i + 2)];
+        catmull_rom_scalar(p, f - i as f32).max(0.)
+    }
+
+    /// Lightness, unit hue direction and the largest in gamut chroma at `t`.
+    fn at(&self, t: f32) -> (f32, f32, f32, f32) {
+        let t = t.clamp(0., 1.);
+        let l = self.lightness.0 + (self.lightness.1 - self.lightness.0) * t;
+        let (b, a) = (self.hue + self.rotation * t).sin_cos();
+        let limit = gamut_mapping::max_chroma(l, a, b) * Self::GAMUT_MARGIN;
+        (l, a, b, limit)
+    }
+
+    /// Plot of the chroma curve with draggable control points, along with the gamut limit.
+    fn curve_ui(&mut self, ui: &mut egui::Ui) {
+        let (response, painter) = ui.allocate_painter(
+            vec2(ui.available_width(), Self::CURVE_HEIGHT),
+            Sense::hover(),
+        );
+        let visuals = ui.visuals().clone();
+        painter.rect(
+            response.rect,
+            0.,
+            visuals.extreme_bg_color,
+            visuals.widgets.noninteractive.bg_stroke,
+        );
+        let rect = response.rect.shrink(Self::HANDLE_RADIUS);
+        let to_screen = |t: f32, c: f32| {
+            pos2(
+                remap(t, 0. ..=1., rect.x_range()),
+                remap(c, 0. ..=Self::MAX_CHROMA, rect.bottom()..=rect.top()),
+            )
+        };
+        let samples = (0..Self::STEPS).map(|i| i as f32 / (Self::STEPS - 1) as f32);
+        let limit: Vec<Pos2> = samples
+            .clone()
+            .map(|t| to_screen(t, self.at(t).3))
+            .collect();
+        painter.add(Shape::line(
+            limit,
+            Stroke::new(1., visuals.weak_text_color()),
+        ));
+        let curve: Vec<Pos2> = samples.map(|t| to_screen(t, self.curve(t))).collect();
+        painter.add(Shape::line(curve, visuals.widgets.noninteractive.fg_stroke));
+        let n = self.chroma.len();
+        for i in 0..n {
+            let t = i as f32 / (n - 1) as f32;
+            let center = to_screen(t, self.chroma[i]);
+            let handle = Rect::from_center_size(center, vec2(1., 1.) * Self::HANDLE_RADIUS * 3.);
+            let handle_response = ui.interact(handle, response.id.with(i), Sense::drag());
+            if handle_response.dragged() {
+                self.chroma[i] = (self.chroma[i]
+                    - handle_response.drag_delta().y / rect.height() * Self::MAX_CHROMA)
+                    .clamp(0., Self::MAX_CHROMA);
+            }
+            let (l, a, b, limit) = self.at(t);
+            let c = self.chroma[i].min(limit);
+            painter.circle(
+                center,
+                Self::HANDLE_RADIUS,
+                srgb_to_color32(oklab_to_srgb_clipped(Oklab::new(l, c * a, c * b))),
+                ui.style().interact(&handle_response).fg_stroke,
+            );
+        }
+    }
+}
+
+impl designer::Designer for Gradient {
+    fn show_ui(&mut self, ui: &mut eframe::egui::Ui) -> bool {
+        let mut c = self.clone();
+        ui.vertical(|ui| {
+            range_sliders(
+                ui,
+                &mut c.lightness,
+                ["L start", "L end"],
+                0. ..=1.,
+                Self::LIGHTNESS_DEFAULT,
+            );
+            resettable_slider(ui, &mut c.hue, "hue", 0. ..=PI * 2., Self::HUE_DEFAULT);
+            resettable_slider(
+                ui,
+                &mut c.rotation,
+                "rotation",
+                -PI * 2.0..=PI * 2.,
+                Self::ROTATION_DEFAULT,
+            );
+            ui.horizontal(|ui| {
+                ui.label("chroma");
+                if ui.button("+").clicked() && c.chroma.len() < 9 {
+                    // resample the curve with one more point
+                    let n = c.chroma.len() + 1;
+                    c.chroma = (0..n).map(|i| c.curve(i as f32 / (n - 1) as f32)).collect();
+                }
+                if ui.button("-").clicked() && c.chroma.len() > 2 {
+                    let n = c.chroma.len() - 1;
+                    c.chroma = (0..n).map(|i| c.curve(i as f32 / (n - 1) as f32)).collect();
+                }
+            });
+            c.curve_ui(ui);
+            ui.weak("the gray line is the most chroma that stays in gamut");
+        });
+        if c != *self {
+            c.check();
+        }
+        match c.monotonicity {
+            Monotonicity::Increasing | Monotonicity::Decreasing => {
+                ui.label("lightness is strictly monotonic")
+            }
+            Monotonicity::Constant => ui.colored_label(Color32::LIGHT_RED, "lightness is constant"),
+            Monotonicity::NonMonotonic(n) => ui.colored_label(
+                Color32::LIGHT_RED,
+                format!("lightness is not monotonic, it changes direction {n} times"),
+            ),
+        };
+        if c != *self {
+            *self = c;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn sample(&self, x: f32, _y: f32) -> Oklab {
+        let (l, a, b, limit) = self.at(x);
+        let c = self.curve(x).min(limit);
+        Oklab::new(l, c * a, c * b)
+    }
+
+    fn render(&self, size: (usize, usize), region: Rect, buf: &mut [Srgb]) {
+        render_par(size, region, buf, |x, y| {
+            oklab_to_srgb_clipped(self.sample(x, y))
+        });
+    }
+
+    fn path(&self) -> Vec<Oklab> {
+        (0..Self::STEPS)
+            .map(|i| self.sample(i as f32 / (Self::STEPS - 1) as f32, 0.5))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{designer::Designer, utils::is_in_gamut};
+
+    #[test]
+    fn stays_in_gamut_and_monotonic() {
+        let gradient = Gradient::new();
+        assert_eq!(gradient.monotonicity, Monotonicity::Increasing);
+        assert!((0..=100).all(|i| is_in_gamut(gradient.sample(i as f32 / 100., 0.5))));
+    }
+}
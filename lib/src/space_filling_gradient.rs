@@ -1,19 +1,16 @@
 use crate::{
-    blur, designer,
+    designer,
     lab_ui::LabUi,
     rotator::Rotator,
     utils::{
-        oklab_to_srgb, oklab_to_srgb_clipped, oklab_to_vec3, render_par_usize, resettable_slider,
-        vec3_to_oklab,
+        oklab_to_srgb, oklab_to_srgb_clipped, oklab_to_vec3, render_par, resettable_slider,
+        smooth_par, vec3_to_oklab,
     },
 };
-use eframe::egui;
-use glam::Quat;
+use eframe::{egui, emath::Rect};
+use glam::{Quat, Vec3};
 use num_bigint::BigUint;
-use palette::{convert::FromColorUnclamped, Oklab, Srgb};
-use rayon::iter::{
-    IndexedParallelIterator, IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator,
-};
+use palette::{Oklab, Srgb};
 
 #[derive(PartialEq, Clone)]
 pub struct Gradient {
@@ -40,6 +37,8 @@ impl Gradient {
     const SMOOTH_DEFAULT: f32 = 0.;
     const LEVELS_DEFAULT: u32 = 3;
     const ROTATION_DEFAULT: Quat = Quat::IDENTITY;
+    /// resolution of the 2d hilbert curve covering the image
+    const BITS_2D: u32 = 9;
     pub fn new() -> Self {
         Self {
             offset: Self::OFFSET_DEFAULT,
@@ -50,6 +49,32 @@ impl Gradient {
             extend: true,
         }
     }
+
+    fn max_id_3d(&self) -> u64 {
+        2_u64.pow(3 * (self.levels + 1)) - 1
+    }
+
+    /// A vertex of the 3d hilbert curve, transformed into Oklab coordinates.
+    fn curve_point(&self, hid: u64) -> Vec3 {
+        let bits_3d = self.levels + 1;
+        let size_3d = 2_u32.pow(bits_3d);
+        let level_size = 0.5 * (2. - 2_f32.powi(-(self.levels as i32)));
+        let p3 =
+            hilbert::Point::new_from_hilbert_index(0, &BigUint::from(hid), bits_3d as usize, 3);
+        let mut v3 = Vec3::from_slice(
+            p3.get_coordinates()
+                .iter()
+                .map(|&a| (a as f32 / size_3d as f32 - 0.5) * 2.0 * level_size)
+                .collect::<Vec<_>>()
+                .as_ref(),
+        );
+        v3 = self.rotation * v3;
+        //v3 *= vec3(1., 2., 2.);
+        //v3 += vec3(-0.5, -1., -1.);
+        v3 *= oklab_to_vec3(self.scale);
+        v3.x += 0.5;
+        v3 + oklab_to_vec3(self.offset)
+    }
 }
 
 impl designer::Designer for Gradient {
@@ -68,7 +93,8 @@ impl designer::Designer for Gradient {
             ui.add(
                 LabUi::new(offset, "offset")
                     .default_value(Self::OFFSET_DEFAULT)
-                    .l_range(-1.0..=1.0),
+                    .l_range(-1.0..=1.0)
+                    .picker(false),
             );
             let scale_range = 0.01..=2.0;
             ui.add(
@@ -76,7 +102,8 @@ impl designer::Designer for Gradient {
                     .default_value(Self::SCALE_DEFAULT)
                     .l_range(scale_range.clone())
                     .a_range(scale_range.clone())
-                    .b_range(scale_range),
+                    .b_range(scale_range)
+                    .picker(false),
             );
             ui.add_space(SPACE);
             ui.label("rotation");
@@ -104,61 +131,29 @@ impl designer::Designer for Gradient {
         }
     }
 
-    fn render(&self, size: (usize, usize), buf: &mut [Srgb]) {
-        assert!(size.0 == size.1);
-        assert!(size.0.is_power_of_two());
-        let bits_2d = usize::BITS - size.0.leading_zeros() - 1;
-        let maxid_2d = size.0.pow(2) - 1;
-        let bits_3d = self.levels + 1;
-        let size_3d = 2_u32.pow(bits_3d);
-        let maxid_3d = size_3d.pow(3) - 1;
-        let level_size = 0.5 * (2. - 2_f32.powi(-(self.levels as i32)));
-        render_par_usize(size, buf, |x, y| {
-            let hid_2d =
-                hilbert::Point::new(0, &[x as u32, y as u32]).hilbert_transform(bits_2d as usize);
-            let t = u64::try_from(hid_2d).unwrap() as f64 / maxid_2d as f64;
-            debug_assert!(t <= 1.);
-            let hid_3d_f = maxid_3d as f64 * t;
-            let hid_lower_3d = hid_3d_f as u64;
-            debug_assert!(hid_lower_3d <= maxid_3d as u64);
-            let f = hid_3d_f.fract();
-            let p3_lower = hilbert::Point::new_from_hilbert_index(
-                0,
-                &BigUint::from(hid_lower_3d),
-                bits_3d as usize,
-                3,
-            );
-            let v3_lower = glam::Vec3::from_slice(
-                p3_lower
-                    .get_coordinates()
-                    .iter()
-                    .map(|&a| (a as f32 / size_3d as f32 - 0.5) * 2.0 * level_size)
-                    .collect::<Vec<_>>()
-                    .as_ref(),
-            );
-            let p3_upper = hilbert::Point::new_from_hilbert_index(
-                0,
-                &BigUint::from(hid_lower_3d + 1),
-                bits_3d as usize,
-                3,
-            );
-            let v3_upper = glam::Vec3::from_slice(
-                p3_upper
-                    .get_coordinates()
-                    .iter()
-                    .map(|&a| (a as f32 / size_3d as f32 - 0.5) * 2.0 * level_size)
-                    .collect::<Vec<_>>()
-                    .as_ref(),
-            );
-            let mut v3 = v3_lower.lerp(v3_upper, f as f32);
-            v3 = self.rotation * v3;
-            //v3 *= vec3(1., 2., 2.);
-            //v3 += vec3(-0.5, -1., -1.);
-            v3 *= oklab_to_vec3(self.scale);
-            v3.x += 0.5;
-            v3 += oklab_to_vec3(self.offset);
+    fn sample(&self, x: f32, y: f32) -> Oklab {
+        let size_2d = 2_u32.pow(Self::BITS_2D);
+        let maxid_2d = size_2d.pow(2) - 1;
+        let maxid_3d = self.max_id_3d();
+        // outside of the design we just extend the edges. negative values saturate to 0
+        let x2d = ((x * size_2d as f32) as u32).min(size_2d - 1);
+        let y2d = ((y * size_2d as f32) as u32).min(size_2d - 1);
+        let hid_2d = hilbert::Point::new(0, &[x2d, y2d]).hilbert_transform(Self::BITS_2D as usize);
+        let t = u64::try_from(hid_2d).unwrap() as f64 / maxid_2d as f64;
+        debug_assert!(t <= 1.);
+        let hid_3d_f = maxid_3d as f64 * t;
+        let hid_lower_3d = hid_3d_f as u64;
+        debug_assert!(hid_lower_3d <= maxid_3d);
+        let f = hid_3d_f.fract();
+        vec3_to_oklab(
+            self.curve_point(hid_lower_3d)
+                .lerp(self.curve_point(hid_lower_3d + 1), f as f32),
+        )
+    }
 
-            let lab = vec3_to_oklab(v3);
+    fn render(&self, size: (usize, usize), region: Rect, buf: &mut [Srgb]) {
+        render_par(size, region, buf, |x, y| {
+            let lab = self.sample(x, y);
             if self.extend {
                 oklab_to_srgb_clipped(lab)
             } else {
@@ -166,17 +161,18 @@ impl designer::Designer for Gradient {
             }
         });
         if self.smooth > 0. && self.extend {
-            // TODO have rayon split the work into bigger chunks to reduce sync?
-            let mut labbuf: Vec<_> = buf
-                .par_iter()
-                .map(|c| palette::Oklab::from_color_unclamped(c.into_linear()))
-                .collect();
-            blur::gaussian_blur(labbuf.as_mut(), size.0, size.1, self.smooth);
-            labbuf
-                .par_iter()
-                .copied()
-                .zip(buf.par_iter_mut())
-                .for_each(|(a, b)| *b = oklab_to_srgb_clipped(a));
+            smooth_par(size, region, buf, self.smooth);
         }
     }
+
+    fn path(&self) -> Vec<Oklab> {
+        // at high levels only every n-th vertex is used, which approximates a lower level curve
+        const MAX_POINTS: u64 = 1 << 15;
+        let maxid_3d = self.max_id_3d();
+        let step = (maxid_3d / MAX_POINTS).max(1);
+        (0..=maxid_3d)
+            .step_by(step as usize)
+            .map(|hid| vec3_to_oklab(self.curve_point(hid)))
+            .collect()
+    }
 }
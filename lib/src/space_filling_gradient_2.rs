@@ -1,19 +1,17 @@
 use std::f32::consts::PI;
 
 use crate::{
-    blur, designer,
+    designer,
     lab_ui::LabUi,
     utils::{
         oklab_to_srgb, oklab_to_srgb_clipped, oklab_to_vec3, render_par, resettable_slider,
-        vec3_to_oklab,
+        smooth_par, vec3_to_oklab,
     },
 };
+use eframe::emath::Rect;
 use glam::{vec3, Mat2};
 use num_bigint::BigUint;
-use palette::{convert::FromColorUnclamped, Oklab, Srgb};
-use rayon::iter::{
-    IndexedParallelIterator, IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator,
-};
+use palette::{Oklab, Srgb};
 
 #[derive(PartialEq, Clone)]
 pub struct Gradient {
@@ -66,7 +64,8 @@ impl designer::Designer for Gradient {
             ui.add(
                 LabUi::new(offset, "offset")
                     .default_value(Self::OFFSET_DEFAULT)
-                    .l_range(-1.0..=1.0),
+                    .l_range(-1.0..=1.0)
+                    .picker(false),
             );
             let scale_range = 0.01..=2.0;
             ui.add(
@@ -74,7 +73,8 @@ impl designer::Designer for Gradient {
                     .default_value(Self::SCALE_DEFAULT)
                     .l_range(scale_range.clone())
                     .a_range(scale_range.clone())
-                    .b_range(scale_range),
+                    .b_range(scale_range)
+                    .picker(false),
             );
             resettable_slider(
                 ui,
@@ -97,53 +97,54 @@ impl designer::Designer for Gradient {
         }
     }
 
-    fn render(&self, size: (usize, usize), buf: &mut [Srgb]) {
-        assert!(size.0 == size.1);
-        assert!(size.0.is_power_of_two());
+    fn sample(&self, x: f32, y: f32) -> Oklab {
         let h_bits = self.levels + 1;
         let h_size = 2_u32.pow(h_bits);
         let maxhid = h_size.pow(2) - 1;
         let level_size = 0.5 * (2. - 2_f32.powi(-(self.levels as i32)));
-        render_par(size, buf, |x, y| {
-            let hid_f = maxhid as f32 * x;
-            let hid_lower = hid_f as u64;
-            debug_assert!(hid_lower <= maxhid as u64);
-            let f = hid_f.fract();
-            let p2_lower = hilbert::Point::new_from_hilbert_index(
-                0,
-                &BigUint::from(hid_lower),
-                h_bits as usize,
-                2,
-            );
-            let v2_lower = glam::Vec2::from_slice(
-                p2_lower
-                    .get_coordinates()
-                    .iter()
-                    .map(|&a| (a as f32 / h_size as f32 - 0.5) * 2.0 * level_size)
-                    .collect::<Vec<_>>()
-                    .as_ref(),
-            );
-            let p2_upper = hilbert::Point::new_from_hilbert_index(
-                0,
-                &BigUint::from(hid_lower + 1),
-                h_bits as usize,
-                2,
-            );
-            let v2_upper = glam::Vec2::from_slice(
-                p2_upper
-                    .get_coordinates()
-                    .iter()
-                    .map(|&a| (a as f32 / h_size as f32 - 0.5) * 2.0 * level_size)
-                    .collect::<Vec<_>>()
-                    .as_ref(),
-            );
-            let mut v2 = v2_lower.lerp(v2_upper, f);
-            v2 = Mat2::from_angle(self.rotation) * v2;
-            let mut v3 = vec3(1. - y, v2.x, v2.y);
-            v3 *= oklab_to_vec3(self.scale);
-            v3 += oklab_to_vec3(self.offset);
+        // outside of the design we just extend the ends of the curve
+        let hid_f = (maxhid as f32 * x).clamp(0., maxhid as f32);
+        let hid_lower = (hid_f as u64).min(maxhid as u64 - 1);
+        let f = hid_f - hid_lower as f32;
+        let p2_lower = hilbert::Point::new_from_hilbert_index(
+            0,
+            &BigUint::from(hid_lower),
+            h_bits as usize,
+            2,
+        );
+        let v2_lower = glam::Vec2::from_slice(
+            p2_lower
+                .get_coordinates()
+                .iter()
+                .map(|&a| (a as f32 / h_size as f32 - 0.5) * 2.0 * level_size)
+                .collect::<Vec<_>>()
+                .as_ref(),
+        );
+        let p2_upper = hilbert::Point::new_from_hilbert_index(
+            0,
+            &BigUint::from(hid_lower + 1),
+            h_bits as usize,
+            2,
+        );
+        let v2_upper = glam::Vec2::from_slice(
+            p2_upper
+                .get_coordinates()
+                .iter()
+                .map(|&a| (a as f32 / h_size as f32 - 0.5) * 2.0 * level_size)
+                .collect::<Vec<_>>()
+                .as_ref(),
+        );
+        let mut v2 = v2_lower.lerp(v2_upper, f);
+        v2 = Mat2::from_angle(self.rotation) * v2;
+        let mut v3 = vec3(1. - y, v2.x, v2.y);
+        v3 *= oklab_to_vec3(self.scale);
+        v3 += oklab_to_vec3(self.offset);
+        vec3_to_oklab(v3)
+    }
 
-            let lab = vec3_to_oklab(v3);
+    fn render(&self, size: (usize, usize), region: Rect, buf: &mut [Srgb]) {
+        render_par(size, region, buf, |x, y| {
+            let lab = self.sample(x, y);
             if self.extend {
                 oklab_to_srgb_clipped(lab)
             } else {
@@ -151,17 +152,7 @@ impl designer::Designer for Gradient {
             }
         });
         if self.smooth > 0. && self.extend {
-            // TODO have rayon split the work into bigger chunks to reduce sync?
-            let mut labbuf: Vec<_> = buf
-                .par_iter()
-                .map(|c| palette::Oklab::from_color_unclamped(c.into_linear()))
-                .collect();
-            blur::gaussian_blur(labbuf.as_mut(), size.0, size.1, self.smooth);
-            labbuf
-                .par_iter()
-                .copied()
-                .zip(buf.par_iter_mut())
-                .for_each(|(a, b)| *b = oklab_to_srgb_clipped(a));
+            smooth_par(size, region, buf, self.smooth);
         }
     }
 }
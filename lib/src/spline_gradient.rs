@@ -0,0 +1,279 @@
+use crate::{
+    designer,
+    interpolation::catmull_rom,
+    lab_ui::LabUi,
+    point_cloud::lab_to_view,
+    rotator::{drag_rotation, project},
+    utils::{
+        oklab_to_srgb, oklab_to_srgb_clipped, oklab_to_vec3, render_par, resettable_slider,
+        smooth_par, srgb_to_color32, vec3_to_oklab,
+    },
+};
+use eframe::{
+    egui::{self, Sense},
+    emath::{vec2, Pos2, Rect},
+    epaint::Stroke,
+};
+use glam::{Quat, Vec3};
+use palette::{Oklab, Srgb};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+#[derive(EnumIter, Debug, PartialEq, Default, Copy, Clone)]
+enum Spline {
+    /// passes through all control points
+    #[default]
+    CatmullRom,
+    /// passes through the first and last control point and is pulled towards the others
+    Bezier,
+}
+
+/// The x axis follows a spline through Oklab, the y axis moves along a fixed direction.
+#[derive(PartialEq, Clone)]
+pub struct Gradient {
+    points: Vec<Oklab>,
+    spline: Spline,
+    /// change in color from the top to the bottom of the design
+    y_direction: Oklab,
+    extend: bool,
+    smooth: f32,
+}
+
+impl Gradient {
+    const Y_DIRECTION_DEFAULT: Oklab = Oklab {
+        l: -0.4,
+        a: 0.,
+        b: 0.,
+    };
+    const SMOOTH_DEFAULT: f32 = 0.;
+    const MAX_POINTS: usize = 8;
+    const VIEW_SIZE: f32 = 160.;
+    const SWATCH_SIZE: f32 = 20.;
+    /// samples of the curve in the 3d view
+    const CURVE_STEPS: usize = 128;
+
+    pub fn new() -> Self {
+        Self {
+            points: vec![
+                Oklab::new(0.35, 0.05, -0.15),
+                Oklab::new(0.55, 0.15, 0.),
+                Oklab::new(0.75, 0.05, 0.12),
+                Oklab::new(0.9, -0.05, 0.05),
+            ],
+            spline: Spline::default(),
+            y_direction: Self::Y_DIRECTION_DEFAULT,
+            extend: true,
+            smooth: Self::SMOOTH_DEFAULT,
+        }
+    }
+
+    /// Point on the spline at `t` in `0..=1`.
+    fn curve(&self, t: f32) -> Vec3 {
+        let t = t.clamp(0., 1.);
+        let n = self.points.len();
+        let point = |i: usize| oklab_to_vec3(self.points[i]);
+        match self.spline {
+            Spline::CatmullRom => {
+                let f = t * (n - 1) as f32;
+                let i = (f as usize).min(n - 2) as isize;
+                let at = |j: isize| point(j.clamp(0, n as isize - 1) as usize);
+                catmull_rom([at(i - 1), at(i), at(i + 1), at(i + 2)], f - i as f32)
+            }
+            Spline::Bezier => {
+                // de casteljau, without allocating since this runs for every pixel
+                let mut p = [Vec3::ZERO; Self::MAX_POINTS];
+                for (i, v) in p.iter_mut().take(n).enumerate() {
+                    *v = point(i);
+                }
+                for k in (1..n).rev() {
+                    for i in 0..k {
+                        p[i] = p[i].lerp(p[i + 1], t);
+                    }
+                }
+                p[0]
+            }
+        }
+    }
+
+    /// The curve and its control points, rotated by dragging.
+    /// Clicking a control point selects it.
+    fn view_3d(&self, ui: &mut egui::Ui, selected: &mut usize) {
+        let rotation_id = ui.id().with("spline view rotation");
+        let mut rotation = ui.data(|d| d.get_temp::<Quat>(rotation_id).unwrap_or_default());
+        let (response, painter) = ui.allocate_painter(
+            vec2(Self::VIEW_SIZE, Self::VIEW_SIZE),
+            Sense::click_and_drag(),
+        );
+        drag_rotation(ui, &response, &mut rotation);
+        ui.data_mut(|d| d.insert_temp(rotation_id, rotation));
+        let rect = response.rect;
+        let to_screen = |lab: Oklab| -> Pos2 {
+            rect.center() + project(rotation * lab_to_view(lab)).0 * rect.width() / 2.
+        };
+        let visuals = ui.visuals();
+        painter.rect_filled(rect, 0., visuals.extreme_bg_color);
+        painter.line_segment(
+            [
+                to_screen(Oklab::new(0., 0., 0.)),
+                to_screen(Oklab::new(1., 0., 0.)),
+            ],
+            Stroke::new(1., visuals.weak_text_color()),
+        );
+        // control polygon
+        for w in self.points.windows(2) {
+            painter.line_segment(
+                [to_screen(w[0]), to_screen(w[1])],
+                Stroke::new(1., visuals.weak_text_color()),
+            );
+        }
+        let curve: Vec<Oklab> = (0..=Self::CURVE_STEPS)
+            .map(|i| vec3_to_oklab(self.curve(i as f32 / Self::CURVE_STEPS as f32)))
+            .collect();
+        for w in curve.windows(2) {
+            painter.line_segment(
+                [to_screen(w[0]), to_screen(w[1])],
+                Stroke::new(2., srgb_to_color32(oklab_to_srgb_clipped(w[0]))),
+            );
+        }
+        for (i, &p) in self.points.iter().enumerate() {
+            let stroke = if i == *selected {
+                visuals.selection.stroke
+            } else {
+                visuals.widgets.noninteractive.fg_stroke
+            };
+            painter.circle(
+                to_screen(p),
+                4.,
+                srgb_to_color32(oklab_to_srgb_clipped(p)),
+                stroke,
+            );
+        }
+        if response.clicked() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                const PICK_DISTANCE: f32 = 8.;
+                if let Some((i, _)) = self
+                    .points
+                    .iter()
+                    .map(|&p| to_screen(p).distance(pos))
+                    .enumerate()
+                    .filter(|&(_, d)| d < PICK_DISTANCE)
+                    .min_by(|(_, a), (_, b)| a.total_cmp(b))
+                {
+                    *selected = i;
+                }
+            }
+        }
+    }
+
+    fn swatches(&self, ui: &mut egui::Ui, selected: &mut usize) {
+        ui.horizontal(|ui| {
+            for (i, &p) in self.points.iter().enumerate() {
+                let (response, painter) =
+                    ui.allocate_painter(vec2(Self::SWATCH_SIZE, Self::SWATCH_SIZE), Sense::click());
+                if response.clicked() {
+                    *selected = i;
+                }
+                let visuals = ui.style().interact_selectable(&response, i == *selected);
+                painter.rect(
+                    response.rect,
+                    2.,
+                    srgb_to_color32(oklab_to_srgb_clipped(p)),
+                    visuals.fg_stroke,
+                );
+            }
+        });
+    }
+}
+
+impl designer::Designer for Gradient {
+    fn show_ui(&mut self, ui: &mut eframe::egui::Ui) -> bool {
+        let mut c = self.clone();
+        // which control point is being edited is ui state, not part of the design
+        let selected_id = ui.id().with("selected control point");
+        let mut selected = ui.data(|d| d.get_temp::<usize>(selected_id).unwrap_or(0));
+        ui.vertical(|ui| {
+            selected = selected.min(c.points.len() - 1);
+            c.view_3d(ui, &mut selected);
+            c.swatches(ui, &mut selected);
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(c.points.len() < Self::MAX_POINTS, egui::Button::new("+"))
+                    .on_hover_text("add a point after the selected one")
+                    .clicked()
+                {
+                    let n = c.points.len();
+                    // halfway along the curve to the next point, or continuing past the end
+                    let p = if selected + 1 < n {
+                        c.curve((selected as f32 + 0.5) / (n - 1) as f32)
+                    } else {
+                        2. * oklab_to_vec3(c.points[n - 1]) - oklab_to_vec3(c.points[n - 2])
+                    };
+                    c.points.insert(selected + 1, vec3_to_oklab(p));
+                    selected += 1;
+                }
+                if ui
+                    .add_enabled(c.points.len() > 2, egui::Button::new("−"))
+                    .on_hover_text("remove the selected point")
+                    .clicked()
+                {
+                    c.points.remove(selected);
+                    selected = selected.min(c.points.len() - 1);
+                }
+            });
+            let Gradient {
+                points,
+                spline,
+                y_direction,
+                extend,
+                smooth,
+            } = &mut c;
+            ui.add(LabUi::new(&mut points[selected], "point"));
+            ui.horizontal(|ui| {
+                for s in Spline::iter() {
+                    ui.selectable_value(spline, s, format!("{:?}", s));
+                }
+            });
+            ui.add(
+                LabUi::new(y_direction, "y direction")
+                    .default_value(Self::Y_DIRECTION_DEFAULT)
+                    .l_range(-1.0..=1.0)
+                    .picker(false),
+            );
+            ui.checkbox(extend, "extend");
+            ui.add_enabled_ui(*extend, |ui| {
+                resettable_slider(ui, smooth, "smooth", 0. ..=100., Self::SMOOTH_DEFAULT);
+            });
+        });
+        ui.data_mut(|d| d.insert_temp(selected_id, selected));
+        if c != *self {
+            *self = c;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn sample(&self, x: f32, y: f32) -> Oklab {
+        vec3_to_oklab(self.curve(x) + (y - 0.5) * oklab_to_vec3(self.y_direction))
+    }
+
+    fn render(&self, size: (usize, usize), region: Rect, buf: &mut [Srgb]) {
+        render_par(size, region, buf, |x, y| {
+            let lab = self.sample(x, y);
+            if self.extend {
+                oklab_to_srgb_clipped(lab)
+            } else {
+                oklab_to_srgb(&lab)
+            }
+        });
+        if self.smooth > 0. && self.extend {
+            smooth_par(size, region, buf, self.smooth);
+        }
+    }
+
+    fn path(&self) -> Vec<Oklab> {
+        (0..=Self::CURVE_STEPS)
+            .map(|i| vec3_to_oklab(self.curve(i as f32 / Self::CURVE_STEPS as f32)))
+            .collect()
+    }
+}
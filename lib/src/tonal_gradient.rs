@@ -0,0 +1,285 @@
+use std::fmt::Write;
+
+use crate::{
+    designer::{self, Designer},
+    gamut_mapping,
+    lab_ui::lch_ui,
+    utils::{
+        oklab_to_srgb_clipped, range_sliders, render_par, resettable_slider, save_png, show_error,
+        srgb_to_hex,
+    },
+};
+use eframe::{egui, emath::Rect};
+use native_dialog::FileDialog;
+use palette::{Oklab, Srgb};
+
+#[derive(PartialEq, Clone)]
+struct Seed {
+    name: String,
+    color: Oklab,
+}
+
+/// Tonal ramps for design tokens, one row per seed color and one column per step.
+#[derive(PartialEq, Clone)]
+pub struct Gradient {
+    seeds: Vec<Seed>,
+    /// lightness of the lightest and darkest step
+    lightness: (f32, f32),
+    /// fraction of the maximum in gamut chroma that the steps may use
+    gamut_fraction: f32,
+}
+
+impl Gradient {
+    const STEPS: [u32; 10] = [50, 100, 200, 300, 400, 500, 600, 700, 800, 900];
+    const LIGHTNESS_DEFAULT: (f32, f32) = (0.97, 0.25);
+    const GAMUT_FRACTION_DEFAULT: f32 = 1.;
+    /// size of each swatch in the exported image
+    const SWATCH_PIXELS: usize = 64;
+
+    pub fn new() -> Self {
+        Self {
+            seeds: vec![
+                Seed {
+                    name: "primary".to_owned(),
+                    color: Oklab::new(0.55, -0.03, -0.15),
+                },
+                Seed {
+                    name: "accent".to_owned(),
+                    color: Oklab::new(0.7, 0.12, 0.1),
+                },
+            ],
+            lightness: Self::LIGHTNESS_DEFAULT,
+            gamut_fraction: Self::GAMUT_FRACTION_DEFAULT,
+        }
+    }
+
+    /// The color of a step, with evenly spaced lightness and the seed's hue and chroma,
+    /// reduced where needed to stay in gamut.
+    fn tone(&self, seed: &Seed, step: usize) -> Oklab {
+        let t = step as f32 / (Self::STEPS.len() - 1) as f32;
+        let (lightest, darkest) = self.lightness;
+        let l = lightest + (darkest - lightest) * t;
+        let chroma = seed.color.a.hypot(seed.color.b);
+        if chroma <= 0. {
+            return Oklab::new(l, 0., 0.);
+        }
+        let (a, b) = (seed.color.a / chroma, seed.color.b / chroma);
+        let c = chroma.min(gamut_mapping::max_chroma(l, a, b) * self.gamut_fraction);
+        Oklab::new(l, c * a, c * b)
+    }
+
+    /// All tones as `(name, step, hex)`.
+    fn tokens(&self) -> Vec<(&str, u32, String)> {
+        self.seeds
+            .iter()
+            .flat_map(|seed| {
+                Self::STEPS.iter().enumerate().map(move |(i, &step)| {
+                    (
+                        seed.name.as_str(),
+                        step,
+                        srgb_to_hex(oklab_to_srgb_clipped(self.tone(seed, i))),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    fn css(&self) -> String {
+        let mut css = ":root {\n".to_owned();
+        for (name, step, hex) in self.tokens() {
+            writeln!(css, "  --{}-{step}: {hex};", css_ident(name)).unwrap();
+        }
+        css.push_str("}\n");
+        css
+    }
+
+    /// In the format of the `colors` section of a Tailwind config.
+    fn tailwind_json(&self) -> String {
+        let seeds: Vec<String> = self
+            .seeds
+            .iter()
+            .map(|seed| {
+                let steps: Vec<String> = Self::STEPS
+                    .iter()
+                    .enumerate()
+                    .map(|(i, step)| {
+                        format!(
+                            "    \"{step}\": \"{}\"",
+                            srgb_to_hex(oklab_to_srgb_clipped(self.tone(seed, i)))
+                        )
+                    })
+                    .collect();
+                format!(
+                    "  {}: {{\n{}\n  }}",
+                    json_string(&seed.name),
+                    steps.join(",\n")
+                )
+            })
+            .collect();
+        format!("{{\n{}\n}}\n", seeds.join(",\n"))
+    }
+
+    fn save_text(text: &str, filter: (&str, &[&str])) {
+        if let Ok(Some(path)) = FileDialog::new()
+            .add_filter(filter.0, filter.1)
+            .show_save_single_file()
+        {
+            if let Err(e) = std::fs::write(path, text) {
+                show_error("Error saving tokens", &e);
+            }
+        }
+    }
+
+    fn save_swatches(&self) {
+        if let Ok(Some(path)) = FileDialog::new()
+            .add_filter("PNG Image", &["png"])
+            .show_save_single_file()
+        {
+            let size = (
+                Self::STEPS.len() * Self::SWATCH_PIXELS,
+                self.seeds.len() * Self::SWATCH_PIXELS,
+            );
+            let mut buf = vec![Srgb::default(); size.0 * size.1];
+            self.render(size, crate::utils::UNIT_RECT, &mut buf);
+            save_png(&buf, size, path);
+        }
+    }
+}
+
+impl designer::Designer for Gradient {
+    fn show_ui(&mut self, ui: &mut eframe::egui::Ui) -> bool {
+        let mut c = self.clone();
+        ui.vertical(|ui| {
+            let mut remove = None;
+            for (i, seed) in c.seeds.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut seed.name).desired_width(80.));
+                    if ui.button("🗑").clicked() {
+                        remove = Some(i);
+                    }
+                });
+                ui.push_id(i, |ui| lch_ui(ui, &mut seed.color));
+            }
+            if let Some(i) = remove {
+                c.seeds.remove(i);
+            }
+            if ui.button("+ seed").clicked() {
+                c.seeds.push(Seed {
+                    name: format!("color{}", c.seeds.len() + 1),
+                    color: Oklab::new(0.6, 0.1, 0.),
+                });
+            }
+            range_sliders(
+                ui,
+                &mut c.lightness,
+                ["L 50", "L 900"],
+                0. ..=1.,
+                Self::LIGHTNESS_DEFAULT,
+            );
+            resettable_slider(
+                ui,
+                &mut c.gamut_fraction,
+                "max chroma",
+                0.1..=1.,
+                Self::GAMUT_FRACTION_DEFAULT,
+            );
+            ui.horizontal(|ui| {
+                if ui.button("💾 css").clicked() {
+                    Self::save_text(&c.css(), ("CSS", &["css"]));
+                }
+                if ui.button("💾 tailwind").clicked() {
+                    Self::save_text(&c.tailwind_json(), ("JSON", &["json"]));
+                }
+                if ui.button("💾 png").clicked() {
+                    c.save_swatches();
+                }
+            });
+        });
+        if c != *self {
+            *self = c;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn sample(&self, x: f32, y: f32) -> Oklab {
+        let rows = self.seeds.len();
+        if rows == 0 {
+            return Oklab::new(0., 0., 0.);
+        }
+        let columns = Self::STEPS.len();
+        let row = ((y.clamp(0., 1.) * rows as f32) as usize).min(rows - 1);
+        let column = ((x.clamp(0., 1.) * columns as f32) as usize).min(columns - 1);
+        self.tone(&self.seeds[row], column)
+    }
+
+    fn render(&self, size: (usize, usize), region: Rect, buf: &mut [Srgb]) {
+        render_par(size, region, buf, |x, y| {
+            oklab_to_srgb_clipped(self.sample(x, y))
+        });
+    }
+}
+
+/// `name` with everything that can't be part of a CSS identifier replaced by `-`.
+fn css_ident(name: &str) -> String {
+    let ident: String = name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' || !c.is_ascii() {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    if ident.is_empty() {
+        "color".to_owned()
+    } else {
+        ident
+    }
+}
+
+/// `s` as a quoted and escaped JSON string.
+fn json_string(s: &str) -> String {
+    let mut json = "\"".to_owned();
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::is_in_gamut;
+
+    #[test]
+    fn tones_are_in_gamut() {
+        let gradient = Gradient::new();
+        for seed in &gradient.seeds {
+            let tones: Vec<Oklab> = (0..Gradient::STEPS.len())
+                .map(|i| gradient.tone(seed, i))
+                .collect();
+            assert!(tones.iter().all(|&t| is_in_gamut(t)));
+            assert!(tones.windows(2).all(|w| w[1].l < w[0].l));
+        }
+    }
+
+    #[test]
+    fn names_are_escaped() {
+        assert_eq!(css_ident(" brand blue!"), "brand-blue-");
+        assert_eq!(css_ident(""), "color");
+        assert_eq!(json_string("a\"b\\c\n\u{1}"), r#""a\"b\\c\n\u0001""#);
+    }
+}
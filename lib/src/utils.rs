@@ -2,43 +2,66 @@ use std::ops::RangeInclusive;
 
 use eframe::{
     egui::{self, Ui},
-    emath,
+    emath::{self, pos2, vec2, Rect},
+    epaint::{Color32, Mesh, Shape},
 };
 use glam::{vec3, Vec3};
+use native_dialog::{MessageDialog, MessageType};
 use palette::{convert::FromColorUnclamped, Clamp, Component, FromComponent, Oklab, Srgb};
 use rayon::{
-    iter::{IndexedParallelIterator, ParallelIterator},
+    iter::{
+        IndexedParallelIterator, IntoParallelRefIterator, IntoParallelRefMutIterator,
+        ParallelIterator,
+    },
     slice::ParallelSliceMut,
 };
 
-use crate::gamut_mapping::{self};
+use crate::{
+    blur,
+    gamut_mapping::{self},
+};
 
-pub fn render_par<F, T>(size: (usize, usize), buf: &mut [Srgb<T>], func: F)
-where
-    F: Fn(f32, f32) -> Srgb + Sync,
-    T: Default + Copy + Send + FromComponent<f32> + Component,
-{
-    render_par_usize(size, buf, |x, y| {
-        func(x as f32 / size.0 as f32, y as f32 / size.1 as f32)
-    });
-}
+/// The area covered by a design, in the normalized coordinates passed to render functions.
+pub const UNIT_RECT: Rect = Rect::from_min_max(pos2(0., 0.), pos2(1., 1.));
+
+/// Size in pixels of the image that smoothing radii are specified relative to.
+const SMOOTH_REFERENCE_SIZE: f32 = 512.;
 
-pub fn render_par_usize<F, T>(size: (usize, usize), buf: &mut [Srgb<T>], func: F)
+pub fn render_par<F, T>(size: (usize, usize), region: Rect, buf: &mut [Srgb<T>], func: F)
 where
-    F: Fn(usize, usize) -> Srgb + Sync,
+    F: Fn(f32, f32) -> Srgb + Sync,
     T: Default + Copy + Send + FromComponent<f32> + Component,
 {
     assert!(buf.len() == size.0 * size.1);
     buf.par_chunks_exact_mut(size.0)
         .enumerate()
         .for_each(|(y, row)| {
+            let yf = region.min.y + y as f32 / size.1 as f32 * region.height();
             row.iter_mut().enumerate().for_each(|(x, pixel)| {
-                let p: Srgb<T> = func(x, y).into_format();
+                let xf = region.min.x + x as f32 / size.0 as f32 * region.width();
+                let p: Srgb<T> = func(xf, yf).into_format();
                 *pixel = p;
             });
         });
 }
 
+/// Blur an image in Oklab space and map the result back into gamut.
+/// `smooth` is the blur radius in pixels of a 512 pixel image of the whole design.
+pub fn smooth_par(size: (usize, usize), region: Rect, buf: &mut [Srgb], smooth: f32) {
+    let sigma = smooth * size.0 as f32 / (region.width() * SMOOTH_REFERENCE_SIZE);
+    // TODO have rayon split the work into bigger chunks to reduce sync?
+    let mut labbuf: Vec<_> = buf
+        .par_iter()
+        .map(|c| palette::Oklab::from_color_unclamped(c.into_linear()))
+        .collect();
+    blur::gaussian_blur(labbuf.as_mut(), size.0, size.1, sigma);
+    labbuf
+        .par_iter()
+        .copied()
+        .zip(buf.par_iter_mut())
+        .for_each(|(a, b)| *b = oklab_to_srgb_clipped(a));
+}
+
 pub fn vec3_to_oklab(vec: Vec3) -> Oklab {
     Oklab::new(vec.x, vec.y, vec.z)
 }
@@ -57,23 +80,66 @@ pub const NEUTRAL_LAB: Oklab = Oklab {
     // b: (Oklab::<f32>::max_b() + Oklab::<f32>::min_b()) / 2.,
 };
 
-pub fn oklab_to_srgb_clipped(lab: palette::Oklab) -> Srgb<f32> {
+fn gamut_map(lab: palette::Oklab) -> gamut_mapping::LinearRGB {
     let linear = gamut_mapping::oklab_to_linear_srgb(gamut_mapping::OKLab {
         l: lab.l,
         a: lab.a,
         b: lab.b,
     });
     // TODO make these selectable in gui
-    //let mapped = gamut_mapping::gamut_clip_adaptive_l0_0_5_alpha(linear, 0.);
-    let mapped = gamut_mapping::gamut_clip_adaptive_l0_0_5(linear);
-    //let mapped = gamut_mapping::gamut_clip_adaptive_L0_L_cusp(linear);
-    //let mapped = gamut_mapping::gamut_clip_preserve_chroma(linear);
-    //let mapped = gamut_mapping::gamut_clip_project_to_0_5(linear);
-    //let mapped = gamut_mapping::gamut_clip_project_to_0_5(linear);
-    //let mapped = gamut_mapping::gamut_clip_project_to_l_cusp(linear);
+    //gamut_mapping::gamut_clip_adaptive_l0_0_5_alpha(linear, 0.)
+    gamut_mapping::gamut_clip_adaptive_l0_0_5(linear)
+    //gamut_mapping::gamut_clip_adaptive_L0_L_cusp(linear)
+    //gamut_mapping::gamut_clip_preserve_chroma(linear)
+    //gamut_mapping::gamut_clip_project_to_0_5(linear)
+    //gamut_mapping::gamut_clip_project_to_0_5(linear)
+    //gamut_mapping::gamut_clip_project_to_l_cusp(linear)
+}
+
+pub fn oklab_to_srgb_clipped(lab: palette::Oklab) -> Srgb<f32> {
+    let mapped = gamut_map(lab);
     Srgb::from_linear(palette::LinSrgb::new(mapped.r, mapped.g, mapped.b))
 }
 
+/// Like [`oklab_to_srgb_clipped`] but staying in Oklab.
+pub fn oklab_clipped(lab: palette::Oklab) -> Oklab {
+    let mapped = gamut_mapping::linear_srgb_to_oklab(gamut_map(lab));
+    Oklab::new(mapped.l, mapped.a, mapped.b)
+}
+
+/// Euclidean distance in Oklab, also known as ΔEOK.
+pub fn delta_e_ok(a: Oklab, b: Oklab) -> f32 {
+    (oklab_to_vec3(a) - oklab_to_vec3(b)).length()
+}
+
+pub fn is_in_gamut(lab: palette::Oklab) -> bool {
+    const EPS: f32 = 0.0001;
+    let rgb = gamut_mapping::oklab_to_linear_srgb(gamut_mapping::OKLab {
+        l: lab.l,
+        a: lab.a,
+        b: lab.b,
+    });
+    [rgb.r, rgb.g, rgb.b]
+        .iter()
+        .all(|c| (-EPS..=1. + EPS).contains(c))
+}
+
+pub fn srgb_to_color32(rgb: Srgb) -> Color32 {
+    let c: Srgb<u8> = rgb.into_format();
+    Color32::from_rgb(c.red, c.green, c.blue)
+}
+
+pub fn srgb_to_hex(rgb: Srgb) -> String {
+    let c: Srgb<u8> = rgb.into_format();
+    format!("#{:02x}{:02x}{:02x}", c.red, c.green, c.blue)
+}
+
+/// Parse `#rrggbb` or `#rgb`, with or without the `#`.
+pub fn hex_to_srgb(hex: &str) -> Option<Srgb> {
+    let c: Srgb<u8> = hex.trim().parse().ok()?;
+    Some(c.into_format())
+}
+
 pub fn oklab_to_srgb(lab: &palette::Oklab) -> Srgb<f32> {
     let rgb_unclamped = Srgb::from_color_unclamped(*lab);
     if rgb_unclamped.is_within_bounds() {
@@ -83,6 +149,36 @@ pub fn oklab_to_srgb(lab: &palette::Oklab) -> Srgb<f32> {
     }
 }
 
+pub fn show_error(title: &str, error: &dyn std::fmt::Display) {
+    MessageDialog::new()
+        .set_type(MessageType::Error)
+        .set_title(title)
+        .set_text(&error.to_string())
+        .show_alert()
+        .unwrap();
+}
+
+pub fn save_png<P: AsRef<std::path::Path>>(buf: &[Srgb], size: (usize, usize), path: P) {
+    assert!(buf.len() == size.0 * size.1);
+    let u16buf: Vec<u16> = buf
+        .iter()
+        .flat_map(|p| {
+            let q = p.into_format();
+            [q.red, q.green, q.blue, u16::MAX]
+        })
+        .collect();
+    if let Err(e) = image::ImageBuffer::<image::Rgba<u16>, Vec<u16>>::from_vec(
+        size.0 as u32,
+        size.1 as u32,
+        u16buf,
+    )
+    .unwrap()
+    .save(path)
+    {
+        show_error("Error saving image", &e);
+    }
+}
+
 pub fn resettable_slider_raw<T: emath::Numeric>(
     ui: &mut Ui,
     value: &mut T,
@@ -92,6 +188,10 @@ pub fn resettable_slider_raw<T: emath::Numeric>(
 ) {
     debug_assert!(range.contains(&default_value));
     ui.add(egui::Slider::new(value, range).text(text));
+    reset_button(ui, value, default_value);
+}
+
+fn reset_button<T: emath::Numeric>(ui: &mut Ui, value: &mut T, default_value: T) {
     if ui
         .add_enabled(*value != default_value, egui::Button::new("⟲"))
         .clicked()
@@ -100,6 +200,123 @@ pub fn resettable_slider_raw<T: emath::Numeric>(
     }
 }
 
+/// Like [`resettable_slider_raw`], with the track showing `color` at each value along it.
+/// Stretches where the color is out of gamut are striped.
+pub fn color_slider_raw(
+    ui: &mut Ui,
+    value: &mut f32,
+    text: &str,
+    range: RangeInclusive<f32>,
+    default_value: f32,
+    color: impl Fn(f32) -> Oklab,
+) {
+    debug_assert!(range.contains(&default_value));
+    let slider = ui.add(egui::Slider::new(value, range.clone()).text(text));
+    let track = color_track(ui, &slider, range, *value, color);
+    ui.painter().add(track);
+    reset_button(ui, value, default_value);
+}
+
+pub fn color_slider(
+    ui: &mut Ui,
+    value: &mut f32,
+    text: &str,
+    range: RangeInclusive<f32>,
+    default_value: f32,
+    color: impl Fn(f32) -> Oklab,
+) {
+    ui.horizontal(|ui| color_slider_raw(ui, value, text, range, default_value, color));
+}
+
+/// The colors along the rail of `slider`, to paint over it, along with the handle on top.
+/// Laid out the way `egui::Slider` does it.
+fn color_track(
+    ui: &Ui,
+    slider: &egui::Response,
+    range: RangeInclusive<f32>,
+    value: f32,
+    color: impl Fn(f32) -> Oklab,
+) -> Shape {
+    const SAMPLES: usize = 32;
+    const STRIPE: f32 = 3.;
+    let rect = slider.rect;
+    let rail_radius = ui.painter().round_to_pixel((rect.height() / 4.).max(2.));
+    let track = Rect::from_min_size(
+        pos2(rect.left(), rect.center().y - rail_radius),
+        vec2(ui.spacing().slider_width, 2. * rail_radius),
+    );
+    let handle_radius = rect.height() / 2.5;
+    let handles = track.x_range().shrink(handle_radius);
+    let samples: Vec<Oklab> = (0..=SAMPLES)
+        .map(|i| color(emath::lerp(range.clone(), i as f32 / SAMPLES as f32)))
+        .collect();
+    // the ends of the rail, where the handle can't go, get the end colors
+    let xs = std::iter::once(track.left())
+        .chain(
+            (0..=SAMPLES)
+                .map(|i| emath::lerp(handles.min..=handles.max, i as f32 / SAMPLES as f32)),
+        )
+        .chain(std::iter::once(track.right()));
+    let colors = std::iter::once(samples[0])
+        .chain(samples.iter().copied())
+        .chain(std::iter::once(samples[SAMPLES]));
+    let mut mesh = Mesh::default();
+    for (i, (x, lab)) in xs.zip(colors).enumerate() {
+        let c = srgb_to_color32(oklab_to_srgb_clipped(lab));
+        mesh.colored_vertex(pos2(x, track.top()), c);
+        mesh.colored_vertex(pos2(x, track.bottom()), c);
+        if i > 0 {
+            let v = 2 * i as u32;
+            mesh.add_triangle(v - 2, v - 1, v);
+            mesh.add_triangle(v - 1, v, v + 1);
+        }
+    }
+    let mut shapes = vec![Shape::mesh(mesh)];
+    let stripes = (track.width() / STRIPE).ceil() as usize;
+    for k in 0..stripes {
+        let x = track.left() + k as f32 * STRIPE;
+        let t = ((x + STRIPE / 2. - handles.min) / handles.span()).clamp(0., 1.);
+        if !is_in_gamut(samples[(t * SAMPLES as f32).round() as usize]) {
+            let stripe = Rect::from_x_y_ranges(
+                x..=(x + STRIPE).min(track.right()),
+                track.center().y..=track.bottom(),
+            );
+            let fill = if k % 2 == 0 {
+                Color32::BLACK
+            } else {
+                Color32::WHITE
+            };
+            shapes.push(Shape::rect_filled(stripe, 0., fill));
+        }
+    }
+    let t = emath::remap_clamp(value, range, 0. ..=1.);
+    let visuals = ui.style().interact(slider);
+    let handle = pos2(emath::lerp(handles.min..=handles.max, t), track.center().y);
+    shapes.push(Shape::circle_filled(
+        handle,
+        handle_radius + visuals.expansion,
+        visuals.bg_fill,
+    ));
+    shapes.push(Shape::circle_stroke(
+        handle,
+        handle_radius + visuals.expansion,
+        visuals.fg_stroke,
+    ));
+    Shape::Vec(shapes)
+}
+
+/// A pair of resettable sliders, for the two ends of a range.
+pub fn range_sliders(
+    ui: &mut Ui,
+    value: &mut (f32, f32),
+    labels: [&str; 2],
+    range: RangeInclusive<f32>,
+    default_value: (f32, f32),
+) {
+    resettable_slider(ui, &mut value.0, labels[0], range.clone(), default_value.0);
+    resettable_slider(ui, &mut value.1, labels[1], range, default_value.1);
+}
+
 pub fn resettable_slider<T: emath::Numeric>(
     ui: &mut Ui,
     value: &mut T,
@@ -0,0 +1,178 @@
+use eframe::egui;
+use glam::{vec3, Vec3};
+use palette::Oklab;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use crate::{
+    color_spaces::{
+        cielab_to_oklab, jzazbz_to_xyz, oklab_to_cielab, oklab_to_xyz, xyz_to_jzazbz, xyz_to_oklab,
+        Cam16, Surround, ViewingConditions,
+    },
+    utils::resettable_slider,
+};
+
+#[derive(EnumIter, Debug, PartialEq, Default, Copy, Clone)]
+enum Space {
+    #[default]
+    Oklab,
+    Cielab,
+    Jzazbz,
+    Cam16Ucs,
+}
+
+/// The color space that a designer's Oklab shaped parameters live in.
+///
+/// Coordinates are normalized so that the parameter ranges stay meaningful in every space:
+/// white has a lightness of 1, and sRGB red has the same chroma as it has in Oklab.
+/// Results are converted back to Oklab, so the gamut mapping and export stay the same.
+#[derive(PartialEq, Clone)]
+pub struct WorkingSpace {
+    space: Space,
+    /// for CAM16-UCS
+    conditions: ViewingConditions,
+    /// luminance of white in cd/m², for JzAzBz
+    white_luminance: f32,
+    cam: Cam16,
+    /// lightness and chroma scale from normalized to actual coordinates
+    scale: (f32, f32),
+}
+
+impl WorkingSpace {
+    const WHITE_LUMINANCE_DEFAULT: f32 = 203.;
+    const RED: Oklab = Oklab {
+        l: 0.627_955,
+        a: 0.224_863,
+        b: 0.125_846,
+    };
+
+    pub fn new() -> Self {
+        let conditions = ViewingConditions::default();
+        let mut space = Self {
+            space: Space::default(),
+            conditions,
+            white_luminance: Self::WHITE_LUMINANCE_DEFAULT,
+            cam: Cam16::new(&conditions),
+            scale: (1., 1.),
+        };
+        space.update();
+        space
+    }
+
+    fn forward(&self, lab: Oklab) -> Vec3 {
+        match self.space {
+            Space::Oklab => vec3(lab.l, lab.a, lab.b),
+            Space::Cielab => oklab_to_cielab(lab),
+            Space::Jzazbz => xyz_to_jzazbz(oklab_to_xyz(lab) * self.white_luminance),
+            Space::Cam16Ucs => self.cam.oklab_to_ucs(lab),
+        }
+    }
+
+    fn inverse(&self, v: Vec3) -> Oklab {
+        match self.space {
+            Space::Oklab => Oklab::new(v.x, v.y, v.z),
+            Space::Cielab => cielab_to_oklab(v),
+            Space::Jzazbz => xyz_to_oklab(jzazbz_to_xyz(v) / self.white_luminance),
+            Space::Cam16Ucs => self.cam.ucs_to_oklab(v),
+        }
+    }
+
+    /// Recompute what depends on the settings.
+    fn update(&mut self) {
+        self.cam = Cam16::new(&self.conditions);
+        let white = self.forward(Oklab::new(1., 0., 0.));
+        let red = self.forward(Self::RED);
+        self.scale = (white.x, red.y.hypot(red.z) / Self::RED.a.hypot(Self::RED.b));
+    }
+
+    /// Convert normalized coordinates in this space to Oklab.
+    pub fn to_oklab(&self, lab: Oklab) -> Oklab {
+        let (l, c) = self.scale;
+        self.inverse(vec3(lab.l * l, lab.a * c, lab.b * c))
+    }
+
+    /// Normalized coordinates in this space of an Oklab color, the inverse of [`WorkingSpace::to_oklab`].
+    pub fn coordinates(&self, lab: Oklab) -> Oklab {
+        let (l, c) = self.scale;
+        let v = self.forward(lab);
+        Oklab::new(v.x / l, v.y / c, v.z / c)
+    }
+
+    pub fn is_oklab(&self) -> bool {
+        self.space == Space::Oklab
+    }
+
+    pub fn show_ui(&mut self, ui: &mut egui::Ui) {
+        let old = self.clone();
+        ui.horizontal(|ui| {
+            ui.label("space");
+            for space in Space::iter() {
+                ui.selectable_value(&mut self.space, space, format!("{:?}", space));
+            }
+        });
+        match self.space {
+            Space::Jzazbz => {
+                resettable_slider(
+                    ui,
+                    &mut self.white_luminance,
+                    "white cd/m²",
+                    80. ..=1000.,
+                    Self::WHITE_LUMINANCE_DEFAULT,
+                );
+            }
+            Space::Cam16Ucs => {
+                let default = ViewingConditions::default();
+                resettable_slider(
+                    ui,
+                    &mut self.conditions.adapting_luminance,
+                    "adapting cd/m²",
+                    0.1..=1000.,
+                    default.adapting_luminance,
+                );
+                resettable_slider(
+                    ui,
+                    &mut self.conditions.background_luminance,
+                    "background Y",
+                    1. ..=100.,
+                    default.background_luminance,
+                );
+                ui.horizontal(|ui| {
+                    ui.label("surround");
+                    for surround in Surround::iter() {
+                        ui.selectable_value(
+                            &mut self.conditions.surround,
+                            surround,
+                            format!("{:?}", surround),
+                        );
+                    }
+                });
+            }
+            Space::Oklab | Space::Cielab => {}
+        }
+        if *self != old {
+            self.update();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn white_is_normalized() {
+        let mut working_space = WorkingSpace::new();
+        for space in Space::iter() {
+            working_space.space = space;
+            working_space.update();
+            let white = working_space.to_oklab(Oklab::new(1., 0., 0.));
+            assert!((white.l - 1.).abs() < 0.01, "{space:?} {white:?}");
+            let color = Oklab::new(0.6, 0.1, -0.05);
+            let back = working_space.coordinates(working_space.to_oklab(color));
+            assert!(
+                (back.l - color.l).abs() + (back.a - color.a).abs() + (back.b - color.b).abs()
+                    < 1e-3
+            );
+        }
+    }
+}